@@ -1,110 +1,890 @@
 use crate::{
-    learning::{Curriculum, Lesson, Task, ValidationResult, Validator},
-    ui::Event,
-    vim::{Buffer, Command, CommandParser, Executor},
+    config::{Action, Bindings, Config, Lookup},
+    learning::{
+        challenge::{keystroke_count, ChallengeTaskResult},
+        progress::{unix_now, HistoryEntry},
+        review, Challenge, ChallengeRun, ChallengeSummary, Curriculum, Difficulty, DrillGenerator,
+        Lesson, MotionReference, ProgressStore, Session, SessionKind, SessionTask, Stats, Task,
+        TaskAttempt, ValidationResult, Validator,
+    },
+    ui::{
+        events::MouseAction,
+        layout::{self, Hit},
+        theme::Theme,
+        Event,
+    },
+    vim::{
+        Buffer, Command, CommandParser, CursorPosition, Executor, Key, KeyCode, Mapper, Motion,
+        Options,
+    },
 };
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Lines moved per mouse wheel step.
+const SCROLL_LINES: usize = 3;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AppMode {
     Menu,
     Lesson,
+    Summary,
+    Stats,
+}
+
+/// Optional buffer overlays, each toggled by its own key.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overlays {
+    pub target: bool,
+    pub trail: bool,
+    pub solution: bool,
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Self {
+            target: true,
+            trail: true,
+            solution: true,
+        }
+    }
+}
+
+/// State of the motion reference opened with F1 or `:help`.
+pub struct HelpState {
+    pub query: String,
+    /// Keys go to the search query rather than moving the selection.
+    pub searching: bool,
+    /// Only list the motions of the current lesson or drill.
+    pub lesson_only: bool,
+    pub selected: usize,
+    /// Drives the before/after animation of the selected example.
+    pub opened_at: Instant,
+}
+
+impl HelpState {
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            searching: false,
+            lesson_only: false,
+            selected: 0,
+            opened_at: Instant::now(),
+        }
+    }
+}
+
+/// Which line numbers the buffer gutter shows, after Vim's `number` and
+/// `relativenumber` options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineNumbers {
+    #[serde(alias = "off")]
+    None,
+    /// `number`: absolute line numbers.
+    #[default]
+    #[serde(rename = "number", alias = "absolute")]
+    Absolute,
+    /// `relativenumber`: distance from the cursor line, which shows 0.
+    #[serde(alias = "relativenumber")]
+    Relative,
+    /// `number relativenumber`: like relative, but the cursor line shows its
+    /// absolute number.
+    Hybrid,
+}
+
+impl LineNumbers {
+    pub fn next(self) -> Self {
+        match self {
+            LineNumbers::None => LineNumbers::Absolute,
+            LineNumbers::Absolute => LineNumbers::Relative,
+            LineNumbers::Relative => LineNumbers::Hybrid,
+            LineNumbers::Hybrid => LineNumbers::None,
+        }
+    }
+
+    fn from_flags(number: bool, relativenumber: bool) -> Self {
+        match (number, relativenumber) {
+            (false, false) => LineNumbers::None,
+            (true, false) => LineNumbers::Absolute,
+            (false, true) => LineNumbers::Relative,
+            (true, true) => LineNumbers::Hybrid,
+        }
+    }
+
+    /// Whether `number` and `relativenumber` are on.
+    fn flags(self) -> (bool, bool) {
+        match self {
+            LineNumbers::None => (false, false),
+            LineNumbers::Absolute => (true, false),
+            LineNumbers::Relative => (false, true),
+            LineNumbers::Hybrid => (true, true),
+        }
+    }
+
+    /// Applies a `:set` argument for `number` or `relativenumber`, such as
+    /// `nu`, `nornu`, `invnumber` or `rnu!`. Returns `None` if `arg` is about
+    /// another option, else the text to show, if any (for `nu?`).
+    pub fn set(&mut self, arg: &str) -> Option<Result<Option<String>, String>> {
+        let end = arg.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(arg.len());
+        let (word, rest) = arg.split_at(end);
+        let (prefix, relative) = ["", "no", "inv"].into_iter().find_map(|prefix| {
+            match word.strip_prefix(prefix)? {
+                "number" | "nu" => Some((prefix, false)),
+                "relativenumber" | "rnu" => Some((prefix, true)),
+                _ => None,
+            }
+        })?;
+
+        let (mut number, mut relativenumber) = self.flags();
+        let defaults = LineNumbers::default().flags();
+        let (flag, default, name) = if relative {
+            (&mut relativenumber, defaults.1, "relativenumber")
+        } else {
+            (&mut number, defaults.0, "number")
+        };
+        match (prefix, rest) {
+            ("", "") => *flag = true,
+            ("no", "") => *flag = false,
+            ("inv", "") | ("", "!") => *flag = !*flag,
+            // VEX's default gutter rather than Vim's, which has no numbers.
+            ("", "&" | "&vim") => *flag = default,
+            ("", "?") => return Some(Ok(Some(format!("{}{}", if *flag { "" } else { "no" }, name)))),
+            _ => return Some(Err(format!("E474: Invalid argument: {}", arg))),
+        }
+        *self = Self::from_flags(number, relativenumber);
+        Some(Ok(None))
+    }
+
+    /// The Vim settings that produce this gutter.
+    pub fn vim_settings(self) -> &'static str {
+        match self {
+            LineNumbers::None => "nonumber norelativenumber",
+            LineNumbers::Absolute => "number",
+            LineNumbers::Relative => "relativenumber",
+            LineNumbers::Hybrid => "number relativenumber",
+        }
+    }
+}
+
+impl std::str::FromStr for LineNumbers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" | "off" => Ok(LineNumbers::None),
+            "number" | "absolute" => Ok(LineNumbers::Absolute),
+            "relativenumber" | "relative" => Ok(LineNumbers::Relative),
+            "hybrid" => Ok(LineNumbers::Hybrid),
+            _ => Err(format!(
+                "Unknown line numbers '{}': use none, number, relative or hybrid",
+                s
+            )),
+        }
+    }
 }
 
 pub struct App {
     pub mode: AppMode,
     pub curriculum: Curriculum,
-    pub current_lesson_id: Option<usize>,
+    pub menu_index: usize,
+    /// Index into `Challenge::builtin()` of the challenge `c` starts from
+    /// the menu; `C` selects the next one.
+    pub challenge_index: usize,
+    pub session: Option<Session>,
+    pub drill: Option<DrillGenerator>,
+    pub challenge: Option<ChallengeRun>,
+    pub summary: Option<ChallengeSummary>,
     pub current_task_index: usize,
     pub task_buffer: Buffer,
     pub command_parser: CommandParser,
     pub validator: Validator,
     pub executed_commands: Vec<Command>,
+    pub overlays: Overlays,
+    pub line_numbers: LineNumbers,
+    /// Keys for the lesson controls.
+    pub bindings: Bindings,
+    /// Keys typed so far of a binding longer than one key.
+    pub pending_keys: Vec<Key>,
+    /// User mappings, applied to keys typed in lessons.
+    pub mapper: Mapper,
+    /// The time given to the latest [`App::tick`]. Keys count as typed then
+    /// when waiting for a longer mapping, and task and challenge times are
+    /// measured on it.
+    pub clock: Instant,
+    /// Vim options the motions follow, changed with `:set`.
+    pub options: Options,
+    pub help: Option<HelpState>,
+    /// Text typed after `:`, while the command line is open.
+    pub command_line: Option<String>,
+    /// Last known terminal size, if the app runs in a terminal.
+    pub terminal_size: Option<(u16, u16)>,
+    /// Where the learner guesses a "where is this?" quiz leaves the cursor.
+    pub guess: Option<CursorPosition>,
+    /// Lines the instructions are scrolled down by.
+    pub instruction_scroll: u16,
+    /// First buffer line shown after scrolling with the mouse; `None`
+    /// follows the cursor.
+    pub buffer_scroll: Option<usize>,
+    /// Cursor positions visited during the current attempt, oldest first.
+    pub trail: Vec<CursorPosition>,
     pub input_buffer: String,
     pub feedback_message: String,
     pub show_hint: bool,
     pub hint_index: usize,
     pub running: bool,
     pub waiting_for_next_task: bool,
+    pub progress: ProgressStore,
+    pub task_started_at: Instant,
+    pub task_attempts: u32,
+    pub task_mistakes: u32,
+    pub task_hints_used: u32,
+    pub task_completed: bool,
+    pub task_keystrokes: usize,
+    pub lesson_scores: Vec<u32>,
+    pub theme: Theme,
+    /// Seed for drills and challenges started from the menu; the current
+    /// time when unset. Recordings fix it so replays generate the same tasks.
+    pub seed: Option<u64>,
+    /// The Unix time reviews are due by; the current time when unset.
+    /// Recordings fix it so replays find the same tasks due.
+    pub review_time: Option<u64>,
 }
 
 impl App {
     pub fn new() -> Self {
+        Self::with_progress(ProgressStore::in_memory())
+    }
+
+    pub fn with_progress(progress: ProgressStore) -> Self {
+        let clock = Instant::now();
         Self {
             mode: AppMode::Menu,
             curriculum: Curriculum::new(),
-            current_lesson_id: None,
+            menu_index: 0,
+            challenge_index: 0,
+            session: None,
+            drill: None,
+            challenge: None,
+            summary: None,
             current_task_index: 0,
             task_buffer: Buffer::new(""),
             command_parser: CommandParser::new(),
             validator: Validator::new(),
             executed_commands: Vec::new(),
+            overlays: Overlays::default(),
+            line_numbers: LineNumbers::default(),
+            bindings: Bindings::default(),
+            pending_keys: Vec::new(),
+            mapper: Mapper::new(),
+            clock,
+            options: Options::default(),
+            help: None,
+            command_line: None,
+            terminal_size: None,
+            guess: None,
+            instruction_scroll: 0,
+            buffer_scroll: None,
+            trail: Vec::new(),
             input_buffer: String::new(),
             feedback_message: String::new(),
             show_hint: false,
             hint_index: 0,
             running: true,
             waiting_for_next_task: false,
+            progress,
+            task_started_at: clock,
+            task_attempts: 1,
+            task_mistakes: 0,
+            task_hints_used: 0,
+            task_completed: false,
+            task_keystrokes: 0,
+            lesson_scores: Vec::new(),
+            theme: Theme::default(),
+            seed: None,
+            review_time: None,
+        }
+    }
+
+    /// Takes the keybindings, mappings and lesson defaults from `config`.
+    /// The theme and profile are left to the caller, which knows about
+    /// command line overrides.
+    pub fn apply_config(&mut self, config: &Config) -> Result<(), String> {
+        self.bindings = config.keys.bindings()?;
+        self.mapper = config.mappings.mapper(config.keys.leader())?;
+        self.options = config.options.options()?;
+        self.task_buffer.set_options(self.options.clone());
+        self.validator.reset_key = self.bindings.label(Action::Reset);
+        self.overlays = config.lesson.overlays();
+        if let Some(numbers) = config.lesson.numbers {
+            self.line_numbers = numbers;
         }
+        Ok(())
     }
 
+    /// The curriculum lesson being played, if the current session is a lesson.
     pub fn current_lesson(&self) -> Option<&Lesson> {
-        self.current_lesson_id
+        self.session
+            .as_ref()
+            .and_then(|session| session.lesson_id())
             .and_then(|id| self.curriculum.get_lesson(id))
     }
 
+    /// Lesson ids in the order the menu lists them.
+    pub fn menu_lesson_ids(&self) -> Vec<usize> {
+        self.curriculum
+            .sections()
+            .into_iter()
+            .flat_map(|(_, lessons)| lessons.into_iter().map(|l| l.id))
+            .collect()
+    }
+
+    pub fn selected_lesson(&self) -> Option<&Lesson> {
+        self.menu_lesson_ids()
+            .get(self.menu_index)
+            .and_then(|&id| self.curriculum.get_lesson(id))
+    }
+
     pub fn current_task(&self) -> Option<&Task> {
-        self.current_lesson()
-            .and_then(|lesson| lesson.tasks.get(self.current_task_index))
+        self.session
+            .as_ref()
+            .and_then(|session| session.tasks.get(self.current_task_index))
+            .map(|session_task| &session_task.task)
+    }
+
+    /// Motions the learner is currently practising: the lesson being played
+    /// (or selected in the menu), or the motions of a drill or challenge.
+    pub fn active_motions(&self) -> Option<Vec<Motion>> {
+        let lesson = match self.mode {
+            AppMode::Menu => self.selected_lesson(),
+            _ => self.current_lesson(),
+        };
+
+        if let Some(lesson) = lesson {
+            return Some(lesson.commands.iter().filter_map(|c| c.as_motion()).collect());
+        }
+        if let Some(run) = &self.challenge {
+            return Some(run.challenge.motions());
+        }
+        self.drill.as_ref().map(|generator| generator.motions().to_vec())
+    }
+
+    /// Reference entries shown by the help overlay, after search and filter.
+    pub fn help_entries(&self) -> Vec<MotionReference> {
+        let Some(help) = &self.help else {
+            return Vec::new();
+        };
+        let allowed = self.active_motions().filter(|_| help.lesson_only);
+
+        MotionReference::all()
+            .into_iter()
+            .filter(|entry| allowed.as_ref().is_none_or(|motions| motions.contains(&entry.motion)))
+            .filter(|entry| entry.matches(&help.query))
+            .collect()
     }
 
     pub fn handle_event(&mut self, event: Event) {
+        // Keys typed while the "terminal too small" message is shown would
+        // act on a screen the learner cannot see.
+        let too_small = self
+            .terminal_size
+            .is_some_and(|(width, height)| !crate::ui::layout::fits(width, height));
+        if too_small && matches!(event, Event::Key(_) | Event::Mouse { .. }) {
+            return;
+        }
+
         match event {
             Event::Quit => {
                 self.running = false;
             }
-            Event::Key(c) => {
-                self.handle_key(c);
+            Event::Key(key) => {
+                if self.command_line.is_some() {
+                    self.handle_command_line_key(key);
+                } else if self.help.is_some() {
+                    self.handle_help_key(key);
+                } else {
+                    self.handle_key(key);
+                }
+            }
+            Event::Resize(width, height) => {
+                self.terminal_size = Some((width, height));
             }
-            Event::SpecialKey(key) => {
-                self.handle_special_key(key);
+            Event::Mouse { action, column, row } => {
+                if self.command_line.is_none() {
+                    self.handle_mouse(action, column, row);
+                }
             }
-            Event::Resize => {}
         }
     }
 
-    fn handle_key(&mut self, c: char) {
-        match self.mode {
-            AppMode::Menu => self.handle_menu_key(c),
-            AppMode::Lesson => self.handle_lesson_key(c),
+    fn handle_mouse(&mut self, action: MouseAction, column: u16, row: u16) {
+        let Some(hit) = layout::hit_test(self, column, row) else {
+            return;
+        };
+
+        match (hit, action) {
+            (Hit::Menu { entry: Some(index) }, MouseAction::Click) => {
+                self.menu_index = index;
+                self.start_selected_lesson();
+            }
+            (Hit::Menu { .. }, MouseAction::ScrollDown) => self.handle_menu_key('j'),
+            (Hit::Menu { .. }, MouseAction::ScrollUp) => self.handle_menu_key('k'),
+            (Hit::Buffer { cell: Some(cell), .. }, MouseAction::Click) => self.click_buffer_cell(cell),
+            (Hit::Buffer { offset, max_offset, .. }, MouseAction::ScrollDown) => {
+                self.buffer_scroll = Some((offset + SCROLL_LINES).min(max_offset));
+            }
+            (Hit::Buffer { offset, .. }, MouseAction::ScrollUp) => {
+                self.buffer_scroll = Some(offset.saturating_sub(SCROLL_LINES));
+            }
+            (Hit::Instructions { max_scroll }, MouseAction::ScrollDown) => {
+                self.instruction_scroll = (self.instruction_scroll + SCROLL_LINES as u16).min(max_scroll);
+            }
+            (Hit::Instructions { .. }, MouseAction::ScrollUp) => {
+                self.instruction_scroll = self.instruction_scroll.saturating_sub(SCROLL_LINES as u16);
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the current task is a "where is this?" quiz still waiting for
+    /// a correct guess.
+    pub fn quiz_pending(&self) -> bool {
+        self.current_task().is_some_and(|task| task.is_prediction())
+            && !self.task_completed
+            && !self.waiting_for_next_task
+    }
+
+    /// Clicking a cell places the guess there; clicking it again checks it.
+    fn click_buffer_cell(&mut self, cell: CursorPosition) {
+        if !self.quiz_pending() {
+            self.feedback_message =
+                "Use motions to move the cursor; clicks only place guesses in quizzes.".to_string();
+            return;
+        }
+
+        if self.guess == Some(cell) {
+            self.check_guess();
+        } else {
+            self.guess = Some(cell);
+            self.feedback_message = "Guess placed. Click it again or press Enter to check.".to_string();
+        }
+    }
+
+    /// Moves the guess marker with h, j, k and l, starting from the cursor.
+    fn handle_guess_key(&mut self, c: char) {
+        let motion = match c {
+            'h' => Motion::Left,
+            'j' => Motion::Down,
+            'k' => Motion::Up,
+            'l' => Motion::Right,
+            _ => {
+                self.feedback_message = "Don't type the keys: move the guess marker with h, j, k, l or click a cell.".to_string();
+                return;
+            }
+        };
+
+        let mut scratch = self.task_buffer.clone();
+        scratch.set_cursor(self.guess.unwrap_or(scratch.cursor()));
+        if Executor::execute(&mut scratch, Command::Motion(motion)).is_ok() {
+            self.guess = Some(scratch.cursor());
+            self.feedback_message = "Press Enter to check your guess.".to_string();
+        }
+    }
+
+    fn check_guess(&mut self) {
+        if !self.quiz_pending() {
+            return;
+        }
+        let Some(guess) = self.guess else {
+            self.feedback_message =
+                "Place a guess first: click a cell or move the marker with h, j, k, l.".to_string();
+            return;
+        };
+
+        if let Some(task) = self.current_task() {
+            let result = self.validator.validate_guess(task, guess);
+            self.handle_validation_result(result);
+        }
+    }
+
+    fn open_help(&mut self, query: &str) {
+        if self.challenge.is_some() {
+            self.feedback_message = "Help is disabled during challenges.".to_string();
+            return;
+        }
+        self.help = Some(HelpState::new(query));
+    }
+
+    fn handle_help_key(&mut self, key: Key) {
+        match key.as_char() {
+            Some(c) => self.handle_help_char(c),
+            None => self.handle_help_special_key(key),
         }
     }
 
-    fn handle_special_key(&mut self, key: crate::ui::events::SpecialKey) {
-        use crate::ui::events::SpecialKey;
+    fn handle_help_char(&mut self, c: char) {
+        let count = self.help_entries().len();
+        let Some(help) = &mut self.help else {
+            return;
+        };
+
+        if help.searching {
+            help.query.push(c);
+            help.selected = 0;
+            return;
+        }
+
+        match c {
+            'j' => help.selected = (help.selected + 1).min(count.saturating_sub(1)),
+            'k' => help.selected = help.selected.saturating_sub(1),
+            'g' => help.selected = 0,
+            'G' => help.selected = count.saturating_sub(1),
+            '/' => {
+                help.searching = true;
+                help.query.clear();
+                help.selected = 0;
+            }
+            'f' => {
+                help.lesson_only = !help.lesson_only;
+                help.selected = 0;
+            }
+            'q' => self.help = None,
+            _ => return,
+        }
 
-        match key {
-            SpecialKey::Escape => {
-                if self.mode == AppMode::Lesson {
+        // Restart the animation so the new selection begins at "before".
+        if let Some(help) = &mut self.help {
+            help.opened_at = Instant::now();
+        }
+    }
+
+    fn handle_help_special_key(&mut self, key: Key) {
+        let Some(help) = &mut self.help else {
+            return;
+        };
+
+        match key.code {
+            KeyCode::Esc if help.searching && !help.query.is_empty() => {
+                help.searching = false;
+                help.query.clear();
+            }
+            KeyCode::Enter => help.searching = false,
+            KeyCode::Backspace if help.searching => {
+                help.query.pop();
+                help.selected = 0;
+            }
+            KeyCode::Esc | KeyCode::F(1) => self.help = None,
+            _ => {}
+        }
+    }
+
+    fn handle_command_line_key(&mut self, key: Key) {
+        if let Some(c) = key.as_char() {
+            self.command_line.get_or_insert_default().push(c);
+            return;
+        }
+
+        match key.code {
+            KeyCode::Enter => {
+                let command = self.command_line.take().unwrap_or_default();
+                self.execute_command_line(&command);
+            }
+            KeyCode::Backspace => {
+                // Like Vim, deleting past the ':' closes the command line.
+                if let Some(text) = &mut self.command_line
+                    && text.pop().is_none()
+                {
+                    self.command_line = None;
+                }
+            }
+            KeyCode::Esc | KeyCode::F(1) => self.command_line = None,
+            _ => {}
+        }
+    }
+
+    /// Runs an Ex-style command typed after `:`.
+    fn execute_command_line(&mut self, command: &str) {
+        let command = command.trim();
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+
+        match name {
+            "" => {}
+            "h" | "help" => self.open_help(argument.trim()),
+            "se" | "set" => self.set_options(argument),
+            "map" | "nmap" | "nnoremap" if argument.is_empty() => {
+                self.feedback_message = if self.mapper.mappings().is_empty() {
+                    "No mapping found".to_string()
+                } else {
+                    let commands: Vec<String> =
+                        self.mapper.mappings().iter().map(|m| m.to_command()).collect();
+                    commands.join("  |  ")
+                };
+            }
+            "q" | "quit" => {
+                if self.mode == AppMode::Menu {
+                    self.running = false;
+                } else {
                     self.return_to_menu();
                 }
             }
-            SpecialKey::Enter => {}
-            SpecialKey::Backspace => {
+            _ => match self.mapper.run(command) {
+                Ok(true) => self.feedback_message = format!(":{}", command),
+                Ok(false) => {
+                    self.feedback_message = format!("Not an editor command: {}", command)
+                }
+                Err(e) => self.feedback_message = e,
+            },
+        }
+    }
+
+    /// `:set`. Like Vim, arguments before a bad one still take effect.
+    /// The current task and drill tasks generated from now on follow the
+    /// new options.
+    fn set_options(&mut self, arguments: &str) {
+        let result = if arguments.trim().is_empty() {
+            Ok(format!("--- Options ---  {}", self.options.changed().join("  ")))
+        } else {
+            self.set_all(arguments)
+        };
+
+        self.task_buffer.set_options(self.options.clone());
+        if let Some(drill) = &mut self.drill {
+            drill.set_options(&self.options);
+        }
+        self.feedback_message = match result {
+            Ok(shown) if shown.is_empty() => format!(":set {}", arguments.trim()),
+            Ok(shown) => shown.trim_end().to_string(),
+            Err(e) => e,
+        };
+    }
+
+    /// `:set` arguments: `number` and `relativenumber` change the gutter,
+    /// the others the motion options. Returns what to show.
+    fn set_all(&mut self, arguments: &str) -> Result<String, String> {
+        let mut shown = Vec::new();
+        for arg in arguments.split_whitespace() {
+            let result = match self.line_numbers.set(arg) {
+                Some(result) => result,
+                None => self.options.set(arg),
+            };
+            shown.extend(result?);
+        }
+        Ok(shown.join("  "))
+    }
+
+    /// Keys typed in lessons go through the user's mappings first.
+    fn handle_key(&mut self, key: Key) {
+        if self.mode != AppMode::Lesson {
+            self.handle_mapped_key(key);
+            return;
+        }
+
+        match self.mapper.feed(key, self.clock) {
+            Ok(keys) => self.handle_mapped_keys(keys),
+            Err(e) => self.feedback_message = e,
+        }
+    }
+
+    /// Sets the clock to `now` and gives up waiting for a longer mapping if
+    /// `timeoutlen` has passed by then. The event loop calls this before
+    /// each event; replays pass the recorded time instead of the real one.
+    pub fn tick(&mut self, now: Instant) {
+        self.clock = now;
+        if self.mapper.timed_out(now) {
+            match self.mapper.flush() {
+                Ok(keys) => self.handle_mapped_keys(keys),
+                Err(e) => self.feedback_message = e,
+            }
+        }
+    }
+
+    /// Handles the result of a mapping like typed keys; a mapping may open
+    /// the command line and type into it.
+    fn handle_mapped_keys(&mut self, keys: Vec<Key>) {
+        for key in keys {
+            if self.command_line.is_some() {
+                self.handle_command_line_key(key);
+            } else if self.help.is_some() {
+                self.handle_help_key(key);
+            } else {
+                self.handle_mapped_key(key);
+            }
+        }
+    }
+
+    fn handle_mapped_key(&mut self, key: Key) {
+        if !self.handle_binding(key) {
+            self.handle_unbound_key(key);
+        }
+    }
+
+    /// Runs the lesson control bound to the keys typed so far, holding keys
+    /// that start a longer binding. Returns false if `key` is not part of a
+    /// binding; held keys that turn out not to be are replayed first.
+    fn handle_binding(&mut self, key: Key) -> bool {
+        if self.mode != AppMode::Lesson
+            || (self.pending_keys.is_empty() && self.command_parser.is_incomplete())
+        {
+            return false;
+        }
+
+        self.pending_keys.push(key);
+        match self.bindings.lookup(&self.pending_keys) {
+            Lookup::Prefix => true,
+            Lookup::Action(action) if action != Action::Continue || self.waiting_for_next_task => {
+                self.pending_keys.clear();
+                self.run_action(action);
+                true
+            }
+            _ => {
+                let mut held = std::mem::take(&mut self.pending_keys);
+                held.pop();
+                if held.is_empty() {
+                    return false;
+                }
+                for held_key in held {
+                    self.handle_unbound_key(held_key);
+                }
+                self.handle_binding(key)
+            }
+        }
+    }
+
+    fn run_action(&mut self, action: Action) {
+        match action {
+            Action::Continue => {
+                self.waiting_for_next_task = false;
+                self.advance_to_next_task();
+            }
+            Action::Hint => {
+                if self.challenge.is_some() {
+                    self.feedback_message = "Hints are disabled during challenges.".to_string();
+                } else {
+                    self.show_next_hint();
+                }
+            }
+            Action::Reset => self.reset_current_task(),
+            Action::ToggleTarget => {
+                self.overlays.target = !self.overlays.target;
+                self.feedback_message = overlay_message("Target marker", self.overlays.target);
+            }
+            Action::ToggleTrail => {
+                self.overlays.trail = !self.overlays.trail;
+                self.feedback_message = overlay_message("Path trail", self.overlays.trail);
+            }
+            Action::ToggleSolution => {
+                self.overlays.solution = !self.overlays.solution;
+                self.feedback_message = overlay_message("Optimal path", self.overlays.solution);
+            }
+            Action::CycleNumbers => {
+                self.line_numbers = self.line_numbers.next();
+                self.feedback_message =
+                    format!("Line numbers: :set {}", self.line_numbers.vim_settings());
+            }
+        }
+    }
+
+    fn handle_unbound_key(&mut self, key: Key) {
+        match key.as_char() {
+            Some(c) => self.handle_char(c),
+            None => self.handle_special_key(key),
+        }
+    }
+
+    fn handle_char(&mut self, c: char) {
+        if c == ':'
+            && matches!(self.mode, AppMode::Menu | AppMode::Lesson)
+            && !self.command_parser.is_incomplete()
+        {
+            self.command_line = Some(String::new());
+            return;
+        }
+
+        match self.mode {
+            AppMode::Menu => self.handle_menu_key(c),
+            AppMode::Lesson => self.handle_lesson_key(c),
+            AppMode::Summary | AppMode::Stats => self.handle_summary_key(c),
+        }
+    }
+
+    fn handle_special_key(&mut self, key: Key) {
+        match key.code {
+            KeyCode::Esc => {
+                // Like Vim, <Esc> first drops a half-typed command.
                 if self.mode == AppMode::Lesson && self.command_parser.is_incomplete() {
-                    self.command_parser.reset();
-                    self.input_buffer.clear();
-                    self.feedback_message = "Command cancelled.".to_string();
+                    self.process_vim_key(key);
+                } else if self.mode != AppMode::Menu {
+                    self.return_to_menu();
                 }
             }
+            KeyCode::Enter => match self.mode {
+                AppMode::Menu => self.start_selected_lesson(),
+                AppMode::Summary | AppMode::Stats => self.return_to_menu(),
+                AppMode::Lesson => self.check_guess(),
+            },
+            KeyCode::F(1) => self.open_help(""),
+            KeyCode::Backspace
+                if self.mode == AppMode::Lesson && self.command_parser.is_incomplete() =>
+            {
+                self.command_parser.reset();
+                self.input_buffer.clear();
+                self.feedback_message = "Command cancelled.".to_string();
+            }
+            KeyCode::Backspace => {}
+            _ if key.is_arrow() => self.feedback_message = arrow_nudge(key),
+            _ if self.mode == AppMode::Lesson && !self.quiz_pending() => self.process_vim_key(key),
+            _ => {}
         }
     }
 
     fn handle_menu_key(&mut self, c: char) {
+        let lesson_count = self.menu_lesson_ids().len();
+
         match c {
-            '1' | '2' | '3' | '4' | '5' | '6' => {
+            'j' => {
+                self.menu_index = (self.menu_index + 1).min(lesson_count.saturating_sub(1));
+            }
+            'k' => {
+                self.menu_index = self.menu_index.saturating_sub(1);
+            }
+            'g' => {
+                self.menu_index = 0;
+            }
+            'G' => {
+                self.menu_index = lesson_count.saturating_sub(1);
+            }
+            'l' | ' ' => {
+                self.start_selected_lesson();
+            }
+            '1'..='9' => {
                 let lesson_id = c.to_digit(10).unwrap() as usize;
                 self.start_lesson(lesson_id);
             }
+            'r' => {
+                self.start_review();
+            }
+            'd' => {
+                let generator = DrillGenerator::new(
+                    Motion::ALL.to_vec(),
+                    DrillGenerator::default_corpus(&self.curriculum),
+                    Difficulty::Medium,
+                    self.seed.unwrap_or_else(unix_now),
+                );
+                self.start_drill(generator);
+            }
+            's' => {
+                self.show_stats();
+            }
+            'c' => {
+                if let Some(challenge) = Challenge::builtin().into_iter().nth(self.challenge_index) {
+                    self.start_challenge(challenge, self.seed.unwrap_or_else(unix_now));
+                }
+            }
+            'C' => {
+                self.select_next_challenge();
+            }
             'q' => {
                 self.running = false;
             }
@@ -112,46 +892,77 @@ impl App {
         }
     }
 
+    fn select_next_challenge(&mut self) {
+        let challenges = Challenge::builtin();
+        self.challenge_index = (self.challenge_index + 1) % challenges.len();
+
+        let challenge = &challenges[self.challenge_index];
+        let best = match self.progress.challenge_best(challenge.name) {
+            Some(best) => format!("personal best {}", best.score),
+            None => "no personal best yet".to_string(),
+        };
+        self.feedback_message = format!(
+            "Challenge: {} ({}, {} tasks), {}. Press c to start, C for the next one.",
+            challenge.title, challenge.keys, challenge.task_count, best
+        );
+    }
+
+    pub fn show_stats(&mut self) {
+        self.mode = AppMode::Stats;
+        self.feedback_message = String::new();
+    }
+
+    /// Statistics for the loaded profile, as of now.
+    pub fn stats(&self) -> Stats {
+        Stats::compute(self.progress.data(), &self.curriculum, unix_now())
+    }
+
+    fn handle_summary_key(&mut self, c: char) {
+        if c == ' ' || c == 'q' {
+            self.return_to_menu();
+        }
+    }
+
     fn handle_lesson_key(&mut self, c: char) {
-        match c {
-            ' ' if self.waiting_for_next_task => {
-                self.waiting_for_next_task = false;
-                self.advance_to_next_task();
-            }
-            '?' if !self.command_parser.is_incomplete() => {
-                self.show_next_hint();
-            }
-            'r' if !self.command_parser.is_incomplete() => {
-                self.reset_current_task();
-            }
-            _ => {
-                self.process_vim_command(c);
-            }
+        if self.quiz_pending() {
+            self.handle_guess_key(c);
+        } else {
+            self.process_vim_key(Key::char(c));
         }
     }
 
     fn reset_current_task(&mut self) {
         self.reset_task_state();
+        if !self.task_completed {
+            self.task_attempts += 1;
+        }
         self.feedback_message = "Task reset. Try again!".to_string();
     }
 
-    fn process_vim_command(&mut self, c: char) {
-        self.input_buffer.push(c);
+    fn process_vim_key(&mut self, key: Key) {
+        self.input_buffer.push_str(&key.to_string());
+        self.task_keystrokes += 1;
 
-        let parse_result = self.command_parser.parse_keystroke(c);
+        let parse_result = self.command_parser.parse_keystroke(key);
 
         match parse_result {
             crate::vim::parser::ParseResult::Complete(command) => {
                 if let Err(e) = Executor::execute(&mut self.task_buffer, command) {
-                    self.feedback_message = format!("Error: {:?}", e);
+                    self.feedback_message = e.to_string();
                 } else {
                     self.executed_commands.push(command);
+                    self.trail.push(self.task_buffer.cursor());
+                    self.buffer_scroll = None;
                     self.check_task_completion();
                 }
             }
             crate::vim::parser::ParseResult::Incomplete => {
                 self.feedback_message = "Command incomplete...".to_string();
             }
+            crate::vim::parser::ParseResult::Cancelled => {
+                self.input_buffer.clear();
+                self.feedback_message = "Command cancelled.".to_string();
+            }
             crate::vim::parser::ParseResult::Invalid(msg) => {
                 self.feedback_message = format!("Invalid command: {}", msg);
                 self.command_parser.reset();
@@ -162,38 +973,220 @@ impl App {
 
     fn check_task_completion(&mut self) {
         if let Some(task) = self.current_task() {
-            let result = self.validator.validate(
-                &task.expected_commands,
+            let result = self.validator.validate_task(
+                task,
                 &self.executed_commands,
                 self.task_buffer.cursor(),
-                task.target_position,
             );
+            self.handle_validation_result(result);
+        }
+    }
 
-            match result {
-                ValidationResult::Correct => {
-                    self.show_hint = false;
-                    self.hint_index = 0;
-
-                    if self.current_task_index + 1 < self.current_lesson().unwrap().tasks.len() {
-                        self.feedback_message = "Correct! Press Space to continue.".to_string();
-                        self.waiting_for_next_task = true;
-                    } else {
-                        self.feedback_message = "Lesson completed! Press ESC to return to menu.".to_string();
-                    }
-                }
-                ValidationResult::Incorrect { feedback } => {
-                    self.feedback_message = feedback;
-                }
-                ValidationResult::WrongPosition { feedback } => {
-                    self.feedback_message = feedback;
+    fn handle_validation_result(&mut self, result: ValidationResult) {
+        if self.challenge.is_some() {
+            self.handle_challenge_result(result);
+            return;
+        }
+
+        match result {
+            ValidationResult::Correct => {
+                self.show_hint = false;
+                self.hint_index = 0;
+
+                let session = self.session.as_ref().unwrap();
+                let is_last_task =
+                    !session.is_endless() && self.current_task_index + 1 >= session.tasks.len();
+
+                if !is_last_task {
+                    self.feedback_message = format!(
+                        "Correct! Press {} to continue.",
+                        self.bindings.label(Action::Continue)
+                    );
+                    self.waiting_for_next_task = true;
+                } else if session.kind == SessionKind::Review {
+                    self.feedback_message = "Review completed! Press ESC to return to menu.".to_string();
+                } else {
+                    self.feedback_message = "Lesson completed! Press ESC to return to menu.".to_string();
                 }
+
+                self.record_task_completion(is_last_task);
             }
+            ValidationResult::InProgress { feedback } => {
+                self.feedback_message = feedback;
+            }
+            ValidationResult::Incorrect { feedback } => {
+                self.task_mistakes += 1;
+                self.feedback_message = feedback;
+            }
+            ValidationResult::WrongPosition { feedback } => {
+                self.task_mistakes += 1;
+                self.feedback_message = if self.overlays.target {
+                    format!("{} The target is highlighted.", feedback)
+                } else {
+                    format!("{} Press 'X' to show the target.", feedback)
+                };
+            }
+        }
+    }
+
+    /// The expected solution's path, shown once the task is solved.
+    pub fn solution_path(&self) -> Option<Vec<CursorPosition>> {
+        if !self.task_completed || !self.overlays.solution {
+            return None;
+        }
+
+        self.current_task().map(|task| task.solution_path())
+    }
+
+    fn handle_challenge_result(&mut self, result: ValidationResult) {
+        let solved = match result {
+            ValidationResult::Correct => true,
+            ValidationResult::Incorrect { .. } | ValidationResult::WrongPosition { .. } => false,
+            ValidationResult::InProgress { feedback } => {
+                self.feedback_message = feedback;
+                return;
+            }
+        };
+
+        let Some(task) = self.current_task() else {
+            return;
+        };
+        let task_result = ChallengeTaskResult {
+            solved,
+            elapsed: self.clock.duration_since(self.task_started_at),
+            keystrokes: self.task_keystrokes,
+            optimal_keystrokes: keystroke_count(&task.expected_commands),
+            motions: task.expected_commands.iter().filter_map(|c| c.as_motion()).collect(),
+        };
+        let points = task_result.points();
+        let task_count = self.session.as_ref().map_or(0, |s| s.tasks.len());
+
+        let Some(run) = &mut self.challenge else {
+            return;
+        };
+        run.results.push(task_result);
+
+        if self.current_task_index + 1 >= task_count {
+            self.finish_challenge();
+        } else {
+            self.advance_to_next_task();
+            self.feedback_message = if solved {
+                format!("+{} points! Next target...", points)
+            } else {
+                "Missed! Next target...".to_string()
+            };
+        }
+    }
+
+    fn finish_challenge(&mut self) {
+        let Some(run) = self.challenge.take() else {
+            return;
+        };
+
+        let mut summary = run.summary();
+        summary.new_best = self.progress.record_challenge(&summary);
+        self.feedback_message = if summary.new_best {
+            "New personal best! Press Enter to return to menu.".to_string()
+        } else {
+            "Challenge finished! Press Enter to return to menu.".to_string()
+        };
+
+        if let Err(e) = self.progress.save() {
+            self.feedback_message
+                .push_str(&format!(" (Progress not saved: {})", e));
+        }
+
+        self.summary = Some(summary);
+        self.session = None;
+        self.mode = AppMode::Summary;
+        self.reset_task_state();
+    }
+
+    fn record_task_completion(&mut self, is_last_task: bool) {
+        if self.task_completed {
+            return;
+        }
+        self.task_completed = true;
+
+        let Some(session) = &self.session else {
+            return;
+        };
+        let origin = session.tasks[self.current_task_index].origin;
+        let lesson_id = session.lesson_id();
+
+        let attempt = TaskAttempt {
+            attempts: self.task_attempts,
+            mistakes: self.task_mistakes,
+            hints_used: self.task_hints_used,
+            elapsed: self.clock.duration_since(self.task_started_at),
+        };
+        self.lesson_scores.push(attempt.score());
+
+        if let Some(origin) = origin {
+            let expected = self.current_task().map_or(0, |t| t.expected_commands.len());
+            self.progress.record_task(origin, attempt, expected);
+        }
+
+        if let Some(task) = self.current_task() {
+            let entry = HistoryEntry {
+                at: unix_now(),
+                lesson_id: origin.map(|o| o.lesson_id),
+                mistakes: self.task_mistakes,
+                hints_used: self.task_hints_used,
+                keystrokes: self.task_keystrokes,
+                // Quizzes are answered without typing the keys.
+                optimal_keystrokes: if task.is_prediction() {
+                    0
+                } else {
+                    keystroke_count(&task.expected_commands)
+                },
+                motions: task.expected_commands.iter().filter_map(|c| c.as_motion()).collect(),
+            };
+            self.progress.record_history(entry);
+        }
+
+        if self.session.as_ref().is_some_and(|s| s.is_endless()) {
+            let average = self.lesson_scores.iter().sum::<u32>() / self.lesson_scores.len() as u32;
+            self.feedback_message = format!(
+                "Correct! Score {} (average {} over {} task(s)). Press {} to continue.",
+                attempt.score(),
+                average,
+                self.lesson_scores.len(),
+                self.bindings.label(Action::Continue)
+            );
+        }
+
+        if is_last_task
+            && let Some(lesson_id) = lesson_id
+        {
+            let score = self.lesson_scores.iter().sum::<u32>() / self.lesson_scores.len() as u32;
+            self.progress.record_lesson_completed(lesson_id, score);
+            self.feedback_message = format!(
+                "Lesson completed with a score of {}! Press ESC to return to menu.",
+                score
+            );
+        }
+
+        if let Err(e) = self.progress.save() {
+            self.feedback_message
+                .push_str(&format!(" (Progress not saved: {})", e));
         }
     }
 
     fn advance_to_next_task(&mut self) {
+        if let (Some(generator), Some(session)) = (&mut self.drill, &mut self.session) {
+            match generator.next_task() {
+                Some(task) => session.tasks.push(SessionTask { task, origin: None }),
+                None => {
+                    self.feedback_message = "Could not generate another drill task.".to_string();
+                    return;
+                }
+            }
+        }
+
         self.current_task_index += 1;
         self.reset_task_state();
+        self.begin_task_tracking();
         self.feedback_message = "Starting next task...".to_string();
     }
 
@@ -211,21 +1204,116 @@ impl App {
         } else if self.hint_index < hints_len - 1 {
             self.hint_index += 1;
         }
+
+        if !self.task_completed {
+            self.task_hints_used = self.task_hints_used.max(self.hint_index as u32 + 1);
+        }
+    }
+
+    fn start_selected_lesson(&mut self) {
+        if let Some(&lesson_id) = self.menu_lesson_ids().get(self.menu_index) {
+            self.start_lesson(lesson_id);
+        }
     }
 
-    fn start_lesson(&mut self, lesson_id: usize) {
-        if self.curriculum.get_lesson(lesson_id).is_some() {
-            self.current_lesson_id = Some(lesson_id);
-            self.current_task_index = 0;
-            self.mode = AppMode::Lesson;
-            self.reset_task_state();
+    pub fn start_lesson(&mut self, lesson_id: usize) {
+        if let Some(lesson) = self.curriculum.get_lesson(lesson_id) {
+            if let Some(index) = self.menu_lesson_ids().iter().position(|&id| id == lesson_id) {
+                self.menu_index = index;
+            }
+
+            let session = Session::from_lesson(lesson);
+            self.start_session(session);
             self.feedback_message = "Lesson started! Complete each task to progress.".to_string();
         }
     }
 
+    /// Starts a session of the tasks that are due for spaced-repetition review.
+    pub fn start_review(&mut self) {
+        let now = self.review_time.unwrap_or_else(unix_now);
+        let items = review::due_items(self.progress.data(), now, review::DAILY_REVIEW_LIMIT);
+
+        if items.is_empty() {
+            self.feedback_message = match review::next_due(self.progress.data()) {
+                Some(due) => {
+                    let hours = due.saturating_sub(now).div_ceil(60 * 60);
+                    format!("Nothing to review yet. Next review due in {} hour(s).", hours)
+                }
+                None => "Nothing to review yet. Complete a lesson first!".to_string(),
+            };
+            return;
+        }
+
+        let session = Session::review(&self.curriculum, &items);
+        let count = session.tasks.len();
+        self.start_session(session);
+        self.feedback_message = format!("Review started: {} task(s) due.", count);
+    }
+
+    /// Starts an endless drill of tasks produced by the given generator.
+    pub fn start_drill(&mut self, generator: DrillGenerator) {
+        let keys: Vec<&str> = generator.motions().iter().map(|m| m.to_key_str()).collect();
+        let title = format!(
+            "Endless Drill ({}, {}, seed {})",
+            keys.join(" "),
+            generator.difficulty().name(),
+            generator.seed()
+        );
+
+        self.start_generated(SessionKind::Drill, title, generator);
+    }
+
+    /// Starts practice on a file; `generator` should draw its tasks from that file.
+    pub fn start_practice(&mut self, file_name: &str, generator: DrillGenerator) {
+        let title = format!("Practice: {} (seed {})", file_name, generator.seed());
+        self.start_generated(SessionKind::Practice, title, generator);
+    }
+
+    fn start_generated(&mut self, kind: SessionKind, title: String, mut generator: DrillGenerator) {
+        generator.set_options(&self.options);
+        let Some(task) = generator.next_task() else {
+            self.feedback_message = "Could not generate a task from those motions.".to_string();
+            return;
+        };
+
+        self.start_session(Session::endless(kind, title, task));
+        self.drill = Some(generator);
+        self.feedback_message = "Started! Reach each target in as few commands as possible.".to_string();
+    }
+
+    /// Starts a timed challenge; the seed fixes which tasks are generated.
+    pub fn start_challenge(&mut self, challenge: Challenge, seed: u64) {
+        let tasks = challenge.generate_tasks(&self.curriculum, seed, &self.options);
+        if tasks.is_empty() {
+            self.feedback_message = "Could not generate tasks for this challenge.".to_string();
+            return;
+        }
+
+        let session = Session::challenge(&challenge, tasks);
+        self.start_session(session);
+        self.challenge = Some(ChallengeRun::new(challenge, self.clock));
+        self.feedback_message = "Go! Reach each target as fast as you can.".to_string();
+    }
+
+    fn start_session(&mut self, session: Session) {
+        self.session = Some(session);
+        self.drill = None;
+        self.challenge = None;
+        self.summary = None;
+        self.current_task_index = 0;
+        self.mode = AppMode::Lesson;
+        self.lesson_scores.clear();
+        self.reset_task_state();
+        self.begin_task_tracking();
+    }
+
     fn return_to_menu(&mut self) {
         self.mode = AppMode::Menu;
-        self.current_lesson_id = None;
+        self.mapper.clear();
+        self.session = None;
+        self.drill = None;
+        self.challenge = None;
+        self.summary = None;
         self.current_task_index = 0;
         self.reset_task_state();
         self.feedback_message = String::new();
@@ -237,22 +1325,197 @@ impl App {
         } else {
             self.task_buffer = Buffer::new("");
         }
+        self.task_buffer.set_options(self.options.clone());
 
         self.executed_commands.clear();
+        self.trail = vec![self.task_buffer.cursor()];
+        self.guess = None;
+        self.instruction_scroll = 0;
+        self.buffer_scroll = None;
         self.input_buffer.clear();
         self.command_parser.reset();
+        self.pending_keys.clear();
         self.show_hint = false;
         self.hint_index = 0;
         self.waiting_for_next_task = false;
     }
 
+    fn begin_task_tracking(&mut self) {
+        self.task_started_at = self.clock;
+        self.task_attempts = 1;
+        self.task_mistakes = 0;
+        self.task_hints_used = 0;
+        self.task_completed = false;
+        self.task_keystrokes = 0;
+    }
+
+    /// Feeds keys in Vim notation (`"wb<Esc>gg"`) as if they were typed.
+    /// Keys still waiting for a longer mapping are resolved at the end, as
+    /// if typing stopped.
+    ///
+    /// ```
+    /// let mut app = vex::App::new();
+    /// app.send_keys("2w"); // lesson 2: move to the start of "quick"
+    /// assert_eq!(app.task_buffer.cursor().column, 4);
+    /// assert!(app.task_completed);
+    /// ```
+    pub fn send_keys(&mut self, keys: &str) {
+        for key in Key::parse_sequence(keys) {
+            self.handle_event(Event::Key(key));
+        }
+        self.wait_for_mappings();
+    }
+
+    /// Lets `timeoutlen` pass, so keys waiting for a longer mapping take
+    /// the mapping they already match.
+    pub fn wait_for_mappings(&mut self) {
+        if let Some(deadline) = self.mapper.deadline() {
+            self.tick(deadline);
+        }
+    }
+
     pub fn is_running(&self) -> bool {
         self.running
     }
 }
 
+/// Arrow keys work in Vim too, but the trainer is about the home row.
+fn arrow_nudge(key: Key) -> String {
+    let home_row = match key.code {
+        KeyCode::Left => 'h',
+        KeyCode::Down => 'j',
+        KeyCode::Up => 'k',
+        _ => 'l',
+    };
+    format!("{} works, but keep your hands on the home row: use '{}' instead.", key, home_row)
+}
+
+fn overlay_message(name: &str, enabled: bool) -> String {
+    format!("{} {}.", name, if enabled { "shown" } else { "hidden" })
+}
+
 impl Default for App {
     fn default() -> Self {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learning::{Language, TaskRef};
+    use std::time::Duration;
+
+    #[test]
+    fn test_perfect_solve_records_no_mistakes() {
+        let mut app = App::new();
+        let (lesson_id, index) = app
+            .curriculum
+            .lessons()
+            .iter()
+            .find_map(|l| {
+                let index = l.tasks.iter().position(|t| t.expected_commands.len() == 3)?;
+                Some((l.id, index))
+            })
+            .unwrap();
+        app.start_lesson(lesson_id);
+        for _ in 0..index {
+            app.advance_to_next_task();
+        }
+
+        let keys: String = app
+            .current_task()
+            .unwrap()
+            .expected_commands
+            .iter()
+            .filter_map(|c| c.as_motion())
+            .map(Motion::to_key_str)
+            .collect();
+        app.send_keys(&keys);
+
+        assert!(app.task_completed);
+        let stats = &app.progress.lesson(lesson_id).unwrap().tasks[&index];
+        assert_eq!((stats.completions, stats.mistakes), (1, 0));
+        assert_eq!(app.progress.data().history.last().unwrap().mistakes, 0);
+    }
+
+    #[test]
+    fn test_set_line_numbers() {
+        let mut app = App::new();
+        app.execute_command_line("set nonu rnu");
+        assert_eq!(app.line_numbers, LineNumbers::Relative);
+        app.execute_command_line("se invnumber ic");
+        assert_eq!(app.line_numbers, LineNumbers::Hybrid);
+        assert!(app.options.ignorecase);
+        app.execute_command_line("set rnu! nu?");
+        assert_eq!(app.line_numbers, LineNumbers::Absolute);
+        assert_eq!(app.feedback_message, "number");
+        app.execute_command_line("set nonumber");
+        assert_eq!(app.line_numbers, LineNumbers::None);
+        app.execute_command_line("set number&");
+        assert_eq!(app.line_numbers, LineNumbers::Absolute);
+
+        app.execute_command_line("set nonu=1");
+        assert_eq!(app.feedback_message, "E474: Invalid argument: nonu=1");
+        assert_eq!(app.line_numbers, LineNumbers::Absolute);
+    }
+
+    #[test]
+    fn test_menu_cycles_challenges() {
+        let mut app = App::new();
+        app.seed = Some(5);
+        app.send_keys("C");
+        assert_eq!(app.challenge_index, 1);
+        assert!(app.feedback_message.starts_with("Challenge: Word Sprint"));
+
+        app.send_keys("c");
+        let session = app.session.as_ref().unwrap();
+        assert_eq!(session.kind, SessionKind::Challenge("word-sprint"));
+
+        let mut app = App::new();
+        app.send_keys(&"C".repeat(Challenge::builtin().len()));
+        assert_eq!(app.challenge_index, 0);
+    }
+
+    #[test]
+    fn test_challenge_times_follow_the_clock() {
+        let mut app = App::new();
+        app.start_challenge(Challenge::builtin().remove(0), 5);
+        let start = app.clock;
+
+        let task = app.current_task().unwrap();
+        let keys: String = task
+            .expected_commands
+            .iter()
+            .filter_map(|c| c.as_motion())
+            .map(|m| m.to_key_str())
+            .collect();
+        app.tick(start + Duration::from_millis(1500));
+        app.send_keys(&keys);
+
+        let run = app.challenge.as_ref().unwrap();
+        assert_eq!(run.results[0].elapsed, Duration::from_millis(1500));
+        assert!(run.results[0].solved);
+    }
+
+    #[test]
+    fn test_practice_highlights_by_file_name() {
+        let mut app = App::new();
+        let buffer = Buffer::new("fn main() {\n    let answer = 42;\n}");
+        let corpus = vec![(buffer, Language::from_path(std::path::Path::new("src/main.rs")))];
+        let generator = DrillGenerator::new(vec![Motion::WordForward], corpus, Difficulty::Easy, 7);
+        app.start_practice("src/main.rs", generator);
+
+        assert_eq!(app.session.as_ref().unwrap().kind, SessionKind::Practice);
+        assert_eq!(app.current_task().unwrap().language, Language::Rust);
+    }
+
+    #[test]
+    fn test_review_keeps_each_lesson_language() {
+        let items = [TaskRef::new(6, 0), TaskRef::new(1, 0)];
+        let session = Session::review(&App::new().curriculum, &items);
+
+        let languages: Vec<Language> = session.tasks.iter().map(|t| t.task.language).collect();
+        assert_eq!(languages, vec![Language::JavaScript, Language::PlainText]);
+    }
+}
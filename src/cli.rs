@@ -0,0 +1,231 @@
+//! The `vex` command line, and setting up an [`App`] from it. Recordings
+//! store the arguments a session was started with, so replays and exports
+//! parse them here again.
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::app::{App, LineNumbers};
+use crate::config::Config;
+use crate::export::ExportFormat;
+use crate::learning::{practice, Challenge, Difficulty, DrillGenerator, Language, TargetKind};
+use crate::ui::theme::{Theme, ThemeName};
+use crate::vim::CommandParser;
+
+#[derive(Parser)]
+#[command(name = "vex")]
+#[command(about = "VEX - Vim Movement Trainer", long_about = None)]
+pub struct Cli {
+    /// Name of the progress profile to load and save [default: default]
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Color theme: dark, light, high-contrast or no-color (NO_COLOR forces no-color) [default: dark]
+    #[arg(long, global = true)]
+    pub theme: Option<ThemeName>,
+
+    /// Buffer line numbers: none, number, relative or hybrid [default: number]
+    #[arg(long, global = true)]
+    pub numbers: Option<LineNumbers>,
+
+    /// Configuration file [default: config.toml in the XDG config dir, e.g. ~/.config/vex/]
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Leave the mouse to the terminal (e.g. for selecting text)
+    #[arg(long, global = true)]
+    pub no_mouse: bool,
+
+    /// Record the session's input with timestamps to FILE (watch it with `vex replay`)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub record: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Start a spaced-repetition review of the tasks that are due today
+    Review,
+    /// Show the progress and statistics dashboard
+    Stats,
+    /// Endless drill of randomly generated tasks
+    Drill {
+        /// Motions to drill, as Vim keys (e.g. "wbe$")
+        #[arg(long, default_value = "hjklwbe0$ggG")]
+        motions: String,
+        /// easy, medium or hard
+        #[arg(long, default_value = "medium")]
+        difficulty: Difficulty,
+        /// Seed for reproducible drills (defaults to the current time)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Timed challenge scored on speed and keystroke efficiency
+    Challenge {
+        /// Challenge to run: hjkl-sprint, word-sprint, line-sprint or full-sprint
+        #[arg(default_value = "hjkl-sprint")]
+        name: String,
+        /// Seed for reproducible tasks (defaults to the current time)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Play a lesson without a terminal and print a JSON report of each task
+    Run {
+        /// Lesson number
+        #[arg(long)]
+        lesson: usize,
+        /// Keys to send, in Vim notation (e.g. "w<Space>ww<Esc>")
+        #[arg(long, conflicts_with = "keys_file", required_unless_present = "keys_file")]
+        keys: Option<String>,
+        /// File with the keys to send; line breaks are ignored and lines
+        /// starting with '#' are comments
+        #[arg(long)]
+        keys_file: Option<PathBuf>,
+    },
+    /// Play back a session recorded with --record
+    Replay {
+        /// Recording to play
+        file: PathBuf,
+        /// Playback speed, from 0.25 to 16 (2 is twice as fast)
+        #[arg(long, default_value_t = 1.0)]
+        speed: f64,
+    },
+    /// Turn a recording into an asciinema cast or plain-text frames
+    Export {
+        /// Recording to export
+        recording: PathBuf,
+        /// asciicast (an asciinema v2 file) or frames (plain-text screens)
+        #[arg(long, default_value = "asciicast")]
+        format: ExportFormat,
+        /// File for the cast or directory for the frames [default: standard output]
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Practice motions on targets in one of your own files
+    Practice {
+        /// File to practice on
+        path: PathBuf,
+        /// Motions to practice, as Vim keys (e.g. "wbe$")
+        #[arg(long, default_value = "hjklwbe0$ggG")]
+        motions: String,
+        /// easy, medium or hard
+        #[arg(long, default_value = "medium")]
+        difficulty: Difficulty,
+        /// Kinds of targets to pick: identifiers, brackets, line-ends
+        #[arg(long, value_delimiter = ',', default_value = "identifiers,brackets,line-ends")]
+        targets: Vec<TargetKind>,
+        /// Seed for reproducible target selection (defaults to the current time)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+impl Cli {
+    /// Applies the configuration and display options, then starts the
+    /// session the command asks for. `seed` is used by drills and challenges
+    /// that were not given one.
+    pub fn configure(&self, app: &mut App, config: &Config, seed: u64) -> Result<(), String> {
+        app.apply_config(config)?;
+        app.theme = Theme::resolve(self.theme.or(config.theme).unwrap_or_default());
+        if let Some(numbers) = self.numbers {
+            app.line_numbers = numbers;
+        }
+
+        match &self.command {
+            Some(Commands::Review) => app.start_review(),
+            Some(Commands::Stats) => app.show_stats(),
+            Some(Commands::Drill {
+                motions,
+                difficulty,
+                seed: drill_seed,
+            }) => {
+                let motions = CommandParser::parse_motions(motions)?;
+                let corpus = DrillGenerator::default_corpus(&app.curriculum);
+                let seed = drill_seed.unwrap_or(seed);
+                app.start_drill(DrillGenerator::new(motions, corpus, *difficulty, seed));
+            }
+            Some(Commands::Challenge {
+                name,
+                seed: challenge_seed,
+            }) => {
+                let challenge = Challenge::find(name).ok_or_else(|| {
+                    let names: Vec<&str> = Challenge::builtin().iter().map(|c| c.name).collect();
+                    format!("Unknown challenge '{}': choose one of {}", name, names.join(", "))
+                })?;
+                app.start_challenge(challenge, challenge_seed.unwrap_or(seed));
+            }
+            Some(Commands::Practice {
+                path,
+                motions,
+                difficulty,
+                targets,
+                seed: practice_seed,
+            }) => {
+                let motions = CommandParser::parse_motions(motions)?;
+                let buffer = practice::load_file(path)
+                    .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+                let corpus = vec![(buffer, Language::from_path(path))];
+                let seed = practice_seed.unwrap_or(seed);
+                let generator = DrillGenerator::new(motions, corpus, *difficulty, seed)
+                    .with_targets(targets.clone());
+                app.start_practice(&path.display().to_string(), generator);
+            }
+            Some(Commands::Run { .. })
+            | Some(Commands::Replay { .. })
+            | Some(Commands::Export { .. })
+            | None => {}
+        }
+
+        Ok(())
+    }
+
+    /// The command line to store in a recording: `args` after `vex`, except
+    /// `--record` and its file.
+    pub fn recorded_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut recorded = Vec::new();
+        let mut skip_value = false;
+
+        for arg in args {
+            if skip_value {
+                skip_value = false;
+            } else if arg == "--record" {
+                skip_value = true;
+            } else if !arg.starts_with("--record=") {
+                recorded.push(arg);
+            }
+        }
+        recorded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learning::SessionKind;
+
+    #[test]
+    fn test_recorded_args() {
+        let args = ["--record", "a.vexrec", "drill", "--record=b.vexrec", "--motions", "wb"];
+        assert_eq!(
+            Cli::recorded_args(args.map(String::from)),
+            vec!["drill", "--motions", "wb"]
+        );
+    }
+
+    #[test]
+    fn test_configure_starts_the_command() {
+        let cli = Cli::try_parse_from(["vex", "--numbers", "relative", "challenge", "word-sprint"]).unwrap();
+        let mut app = App::new();
+        cli.configure(&mut app, &Config::default(), 9).unwrap();
+
+        assert_eq!(app.line_numbers, LineNumbers::Relative);
+        assert_eq!(app.session.unwrap().kind, SessionKind::Challenge("word-sprint"));
+
+        let cli = Cli::try_parse_from(["vex", "challenge", "nope"]).unwrap();
+        let error = cli.configure(&mut App::new(), &Config::default(), 9).unwrap_err();
+        assert!(error.starts_with("Unknown challenge 'nope'"));
+    }
+}
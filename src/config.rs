@@ -0,0 +1,595 @@
+//! The optional configuration file, `config.toml` in VEX's directory under
+//! the XDG config dir (`~/.config/vex/config.toml` on Linux):
+//!
+//! ```toml
+//! profile = "work"
+//! theme = "light"
+//!
+//! [lesson]
+//! numbers = "relative"
+//! solution = false
+//!
+//! [keys]
+//! leader = ","
+//! reset = "<C-r>"
+//! hint = "<leader>h"
+//!
+//! [mappings]
+//! map = ["nnoremap H 0", "nnoremap L $"]
+//!
+//! [options]
+//! set = ["iskeyword+=-", "whichwrap+=h,l", "nostartofline"]
+//! ```
+//!
+//! Command line options take precedence over the file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{LineNumbers, Overlays};
+use crate::ui::theme::ThemeName;
+use crate::vim::parser::ParseResult;
+use crate::vim::mapping::MapCommand;
+use crate::vim::{CommandParser, Key, KeyCode, Mapper, Options};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Progress profile used when `--profile` is not given.
+    pub profile: Option<String>,
+    /// Color theme used when `--theme` is not given.
+    pub theme: Option<ThemeName>,
+    pub lesson: LessonOptions,
+    pub keys: Keybindings,
+    pub mappings: MappingOptions,
+    pub options: OptionSettings,
+    /// A `.vimrc` to take normal-mode mappings and `:set` options from.
+    pub vimrc: Option<PathBuf>,
+}
+
+impl Config {
+    /// `config.toml` in VEX's config directory, if the platform has one.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("vex").join("config.toml"))
+    }
+
+    /// Reads the file given with `--config`, which must exist, or the
+    /// default one, which may not.
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self, String> {
+        match path {
+            Some(path) if !path.exists() => Err(format!("Config file {} not found", path.display())),
+            Some(path) => Self::load(path),
+            None => Self::default_path().map_or(Ok(Self::default()), |path| Self::load(&path)),
+        }
+    }
+
+    /// Reads and checks the file at `path`, and the `.vimrc` it names. A
+    /// missing file gives the defaults.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let mut config = Self::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        if let Some(vimrc) = &config.vimrc {
+            let vimrc = expand_home(vimrc);
+            let text = fs::read_to_string(&vimrc)
+                .map_err(|e| format!("Could not read {}: {}", vimrc.display(), e))?;
+            // Checked as a whole, so that errors give the right line numbers.
+            let mut mapper = Mapper::new();
+            mapper.leader = config.keys.leader();
+            mapper
+                .load_vimrc(&text)
+                .map_err(|e| format!("{}: {}", vimrc.display(), e))?;
+            Options::default()
+                .load_vimrc(&text)
+                .map_err(|e| format!("{}: {}", vimrc.display(), e))?;
+
+            config.options.imported = text
+                .lines()
+                .filter(|line| {
+                    Options::set_arguments(line).is_some_and(|args| args.into_iter().any(Options::is_known))
+                })
+                .map(|line| line.trim().to_string())
+                .collect();
+            config.mappings.imported = text
+                .lines()
+                .filter(|line| MapCommand::parse(line, mapper.leader).is_ok_and(|c| c.is_some()))
+                .map(|line| line.trim().to_string())
+                .collect();
+        }
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let config: Self = toml::from_str(text).map_err(|e| e.message().to_string())?;
+        config.keys.bindings()?;
+        config.mappings.mapper(config.keys.leader())?;
+        config.options.options()?;
+        Ok(config)
+    }
+}
+
+/// Replaces a leading `~` with the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// How lessons and drills start out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LessonOptions {
+    /// Line numbers used when `--numbers` is not given.
+    pub numbers: Option<LineNumbers>,
+    /// Show the target marker.
+    pub target: bool,
+    /// Show the path trail.
+    pub trail: bool,
+    /// Show the optimal path after a task.
+    pub solution: bool,
+    /// Capture the mouse (`--no-mouse` turns it off).
+    pub mouse: bool,
+}
+
+impl LessonOptions {
+    pub fn overlays(&self) -> Overlays {
+        Overlays {
+            target: self.target,
+            trail: self.trail,
+            solution: self.solution,
+        }
+    }
+}
+
+impl Default for LessonOptions {
+    fn default() -> Self {
+        let overlays = Overlays::default();
+        Self {
+            numbers: None,
+            target: overlays.target,
+            trail: overlays.trail,
+            solution: overlays.solution,
+            mouse: true,
+        }
+    }
+}
+
+/// The `[mappings]` table.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MappingOptions {
+    /// Milliseconds to wait for the rest of a mapping, like Vim's
+    /// `timeoutlen`.
+    pub timeoutlen: u64,
+    /// Mapping commands such as `nnoremap H 0`.
+    pub map: Vec<String>,
+    /// The mapping commands of the `vimrc`, read when the config is loaded.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub imported: Vec<String>,
+}
+
+impl Default for MappingOptions {
+    fn default() -> Self {
+        Self {
+            timeoutlen: Mapper::DEFAULT_TIMEOUT.as_millis() as u64,
+            map: Vec::new(),
+            imported: Vec::new(),
+        }
+    }
+}
+
+impl MappingOptions {
+    /// A mapper with the imported mappings, then those of `map`. `leader`
+    /// is what `<leader>` stands for unless the `.vimrc` sets `mapleader`,
+    /// which only applies to the `.vimrc`'s own mappings.
+    pub fn mapper(&self, leader: Key) -> Result<Mapper, String> {
+        let mut mapper = Mapper::new();
+        mapper.timeout = Duration::from_millis(self.timeoutlen);
+        mapper.leader = leader;
+        mapper.load_vimrc(&self.imported.join("\n"))?;
+
+        mapper.leader = leader;
+        for line in &self.map {
+            match mapper.run(line) {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Err(format!("mappings.map: {:?} is not a normal-mode mapping", line));
+                }
+                Err(e) => return Err(format!("mappings.map: {:?}: {}", line, e)),
+            }
+        }
+        Ok(mapper)
+    }
+}
+
+/// The `[options]` table.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OptionSettings {
+    /// `:set` arguments such as `iskeyword+=-` or `nowrapscan`.
+    pub set: Vec<String>,
+    /// The `:set` lines of the `vimrc`, read when the config is loaded.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub imported: Vec<String>,
+}
+
+impl OptionSettings {
+    /// Vim's defaults changed by the imported `:set` lines, then by `set`.
+    pub fn options(&self) -> Result<Options, String> {
+        let mut options = Options::default();
+        options.load_vimrc(&self.imported.join("\n"))?;
+        for arg in &self.set {
+            options
+                .set_all(arg.split_whitespace())
+                .map_err(|e| format!("options.set: {:?}: {}", arg, e))?;
+        }
+        Ok(options)
+    }
+}
+
+/// Lesson controls that are not Vim commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Reset,
+    Hint,
+    /// Move on once a task is solved.
+    Continue,
+    ToggleTarget,
+    ToggleTrail,
+    ToggleSolution,
+    CycleNumbers,
+}
+
+impl Action {
+    pub const ALL: [Action; 7] = [
+        Action::Reset,
+        Action::Hint,
+        Action::Continue,
+        Action::ToggleTarget,
+        Action::ToggleTrail,
+        Action::ToggleSolution,
+        Action::CycleNumbers,
+    ];
+
+    /// The action's key in the `[keys]` table.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Reset => "reset",
+            Action::Hint => "hint",
+            Action::Continue => "continue",
+            Action::ToggleTarget => "target",
+            Action::ToggleTrail => "trail",
+            Action::ToggleSolution => "solution",
+            Action::CycleNumbers => "numbers",
+        }
+    }
+}
+
+/// The `[keys]` table: key sequences in Vim notation, which may use
+/// `<leader>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Keybindings {
+    pub leader: String,
+    pub reset: String,
+    pub hint: String,
+    #[serde(rename = "continue")]
+    pub continue_task: String,
+    pub target: String,
+    pub trail: String,
+    pub solution: String,
+    pub numbers: String,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            leader: "\\".to_string(),
+            reset: "r".to_string(),
+            hint: "?".to_string(),
+            continue_task: "<Space>".to_string(),
+            target: "X".to_string(),
+            trail: "P".to_string(),
+            solution: "O".to_string(),
+            numbers: "N".to_string(),
+        }
+    }
+}
+
+/// Keys VEX itself uses in lessons, which cannot be rebound.
+const RESERVED: [&str; 6] = [":", "<Esc>", "<CR>", "<F1>", "<C-c>", "<C-q>"];
+
+impl Keybindings {
+    fn notation(&self, action: Action) -> &str {
+        match action {
+            Action::Reset => &self.reset,
+            Action::Hint => &self.hint,
+            Action::Continue => &self.continue_task,
+            Action::ToggleTarget => &self.target,
+            Action::ToggleTrail => &self.trail,
+            Action::ToggleSolution => &self.solution,
+            Action::CycleNumbers => &self.numbers,
+        }
+    }
+
+    /// The key `<leader>` stands for; `\` if `leader` is not one key.
+    pub fn leader(&self) -> Key {
+        match Key::parse_sequence(&self.leader)[..] {
+            [key] => key,
+            _ => Key::char('\\'),
+        }
+    }
+
+    /// The keys bound to `action`, with `<leader>` replaced.
+    pub fn sequence(&self, action: Action) -> Vec<Key> {
+        Key::parse_with_leader(self.notation(action), self.leader())
+    }
+
+    /// Resolves and checks every binding. A binding may not start with a key
+    /// the Vim engine understands or one VEX reserves, and no binding may be
+    /// the start of another.
+    pub fn bindings(&self) -> Result<Bindings, String> {
+        let mut errors = Vec::new();
+        if Key::parse_sequence(&self.leader).len() != 1 {
+            errors.push(format!("keys.leader = {:?} must be a single key", self.leader));
+        }
+
+        let entries: Vec<(Action, Vec<Key>)> = Action::ALL
+            .iter()
+            .map(|&action| (action, self.sequence(action)))
+            .collect();
+
+        for (index, (action, keys)) in entries.iter().enumerate() {
+            let name = format!("keys.{} = {:?}", action.name(), self.notation(*action));
+            let Some(&first) = keys.first() else {
+                errors.push(format!("{} is empty", name));
+                continue;
+            };
+
+            if RESERVED.iter().any(|reserved| Key::parse_sequence(reserved) == [first]) {
+                errors.push(format!("{}: {} is reserved by VEX", name, first));
+            } else if starts_vim_command(first) {
+                errors.push(format!("{}: {} is a Vim command VEX teaches", name, first));
+            }
+
+            for (other, other_keys) in &entries[index + 1..] {
+                if keys.starts_with(other_keys) || other_keys.starts_with(keys) {
+                    errors.push(format!(
+                        "{} overlaps keys.{} = {:?}",
+                        name,
+                        other.name(),
+                        self.notation(*other)
+                    ));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(Bindings { entries })
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
+
+fn starts_vim_command(key: Key) -> bool {
+    !matches!(
+        CommandParser::new().parse_keystroke(key),
+        ParseResult::Invalid(_)
+    )
+}
+
+/// What a sequence of typed keys amounts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    Action(Action),
+    /// The start of a longer binding.
+    Prefix,
+    Unbound,
+}
+
+/// Checked keybindings, as built by [`Keybindings::bindings`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bindings {
+    entries: Vec<(Action, Vec<Key>)>,
+}
+
+impl Bindings {
+    pub fn lookup(&self, keys: &[Key]) -> Lookup {
+        let mut lookup = Lookup::Unbound;
+        for (action, bound) in &self.entries {
+            if bound == keys {
+                return Lookup::Action(*action);
+            }
+            if bound.starts_with(keys) {
+                lookup = Lookup::Prefix;
+            }
+        }
+        lookup
+    }
+
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.entries
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map_or(&[], |(_, keys)| keys)
+    }
+
+    /// How to name the binding in the UI: `r`, `<C-r>`, `,h` or `Space`.
+    pub fn label(&self, action: Action) -> String {
+        match self.keys(action) {
+            [key] if key.code == KeyCode::Char(' ') && key.modifiers.is_empty() => {
+                "Space".to_string()
+            }
+            keys => keys.iter().map(|key| key.to_string()).collect(),
+        }
+    }
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Keybindings::default()
+            .bindings()
+            .expect("the default keybindings are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            "theme = \"high-contrast\"\n\
+             [lesson]\nnumbers = \"relativenumber\"\ntrail = false\n\
+             [keys]\nleader = \",\"\nreset = \"<C-r>\"\nhint = \"<leader>h\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.theme, Some(ThemeName::HighContrast));
+        assert_eq!(config.profile, None);
+        assert_eq!(config.lesson.numbers, Some(LineNumbers::Relative));
+        assert!(!config.lesson.overlays().trail);
+        assert!(config.lesson.overlays().target);
+
+        let bindings = config.keys.bindings().unwrap();
+        assert_eq!(bindings.lookup(&[Key::ctrl('r')]), Lookup::Action(Action::Reset));
+        assert_eq!(bindings.lookup(&[Key::char(',')]), Lookup::Prefix);
+        assert_eq!(
+            bindings.lookup(&Key::parse_sequence(",h")),
+            Lookup::Action(Action::Hint)
+        );
+        assert_eq!(bindings.lookup(&[Key::char('r')]), Lookup::Unbound);
+        assert_eq!(bindings.label(Action::Hint), ",h");
+        assert_eq!(bindings.label(Action::Continue), "Space");
+
+        assert!(Config::parse("colour = \"red\"").is_err());
+        assert!(Config::parse("theme = \"pink\"").is_err());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let conflict = |toml: &str| Config::parse(&format!("[keys]\n{}", toml)).unwrap_err();
+
+        assert!(conflict("reset = \"w\"").contains("keys.reset = \"w\": w is a Vim command"));
+        assert!(conflict("hint = \"gx\"").contains("g is a Vim command"));
+        assert!(conflict("hint = \"<Esc>\"").contains("reserved"));
+        assert!(conflict("hint = \"r\"").contains("keys.reset = \"r\" overlaps keys.hint"));
+        assert!(conflict("reset = \"<leader>\"\nhint = \"<leader>h\"").contains("overlaps"));
+        assert!(conflict("trail = \"\"").contains("keys.trail = \"\" is empty"));
+        assert!(conflict("leader = \"ab\"").contains("single key"));
+
+        let errors = conflict("reset = \"h\"\nhint = \"j\"");
+        assert_eq!(errors.split("; ").count(), 2);
+    }
+
+    #[test]
+    fn test_app_uses_bindings() {
+        let config =
+            Config::parse("[keys]\nleader = \",\"\nreset = \"<C-r>\"\nhint = \"<leader>h\"").unwrap();
+        let mut app = crate::App::new();
+        app.apply_config(&config).unwrap();
+        app.send_keys("1");
+
+        app.send_keys(",h");
+        assert!(app.show_hint);
+        assert_eq!(app.task_mistakes, 0);
+
+        // A held leader that starts no binding goes to the lesson as typed.
+        app.send_keys(",l");
+        assert_eq!(app.task_keystrokes, 2);
+        assert_eq!(app.task_buffer.cursor().column, 1);
+
+        app.send_keys("<C-r>");
+        assert_eq!(app.feedback_message, "Task reset. Try again!");
+        app.send_keys("l");
+        assert_eq!(app.feedback_message, "Correct! Press Space to continue.");
+    }
+
+    #[test]
+    fn test_load_with_vimrc() {
+        let dir = std::env::temp_dir().join(format!("vex-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let vimrc = dir.join("vimrc");
+        fs::write(&vimrc, "set number ic\nlet mapleader = \" \"\nnnoremap <leader>e $\n").unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, format!("vimrc = {:?}\n[mappings]\nmap = [\"nmap <leader>e 0\"]", vimrc)).unwrap();
+
+        let config = Config::load(&path).unwrap();
+        fs::write(&vimrc, "nunmap Q\n").unwrap();
+        let error = Config::load(&path).unwrap_err();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(config.mappings.imported, vec!["let mapleader = \" \"", "nnoremap <leader>e $"]);
+        // The vimrc's leader applies to its own mappings only.
+        let mapper = config.mappings.mapper(config.keys.leader()).unwrap();
+        let lhs: Vec<Vec<Key>> = mapper.mappings().iter().map(|m| m.lhs.clone()).collect();
+        assert_eq!(lhs, vec![Key::parse_sequence(" e"), Key::parse_sequence("\\e")]);
+        assert!(error.ends_with("line 1: E31: No such mapping: Q"), "{}", error);
+        assert_eq!(config.options.imported, vec!["set number ic"]);
+        assert!(config.options.options().unwrap().ignorecase);
+        assert!(Config::load(&dir.join("missing.toml")).unwrap() == Config::default());
+    }
+
+    #[test]
+    fn test_app_applies_mappings() {
+        let config = Config::parse(
+            "[mappings]\nmap = [\"nnoremap H 0\", \"nnoremap ; :\", \"nmap Z ?\"]",
+        )
+        .unwrap();
+        let mut app = crate::App::new();
+        app.apply_config(&config).unwrap();
+
+        // Lesson 3 starts with `0`: the mapped key is judged by its command.
+        app.send_keys("3H");
+        assert!(app.task_completed);
+        assert_eq!(app.executed_commands.len(), 1);
+
+        app.send_keys("Z");
+        assert!(app.show_hint);
+
+        app.send_keys(";nmap<CR>");
+        assert!(app.feedback_message.contains("nnoremap H 0"));
+        app.send_keys(";nunmap H<CR>;q<CR>");
+        assert_eq!(app.mode, crate::AppMode::Menu);
+        assert_eq!(app.mapper.mappings().len(), 2);
+
+        assert!(Config::parse("[mappings]\nmap = [\"set number\"]").is_err());
+    }
+
+    #[test]
+    fn test_app_applies_options() {
+        let config = Config::parse("[options]\nset = [\"ww+=l nosol\", \"isk+=-\"]").unwrap();
+        let mut app = crate::App::new();
+        app.apply_config(&config).unwrap();
+        assert!(app.options.wraps('l') && !app.options.startofline);
+
+        app.send_keys("1");
+        assert!(app.task_buffer.options().iskeyword.contains('-'));
+        app.send_keys(":set isk-=- sw?<CR>");
+        assert_eq!(app.feedback_message, "shiftwidth=8");
+        assert!(!app.task_buffer.options().iskeyword.contains('-'));
+
+        app.send_keys(":set ic spell nows<CR>");
+        assert_eq!(app.feedback_message, "E518: Unknown option: spell");
+        assert!(app.options.ignorecase && app.options.wrapscan);
+        app.send_keys(":set<CR>");
+        assert_eq!(
+            app.feedback_message,
+            "--- Options ---  whichwrap=b,s,l  nostartofline  ignorecase"
+        );
+
+        assert!(Config::parse("[options]\nset = [\"sw=x\"]")
+            .unwrap_err()
+            .contains("E521"));
+    }
+}
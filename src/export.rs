@@ -0,0 +1,352 @@
+//! Turning recordings into asciinema casts and plain-text frames, as used by
+//! `vex export`.
+
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier, Style},
+    Terminal,
+};
+use serde_json::json;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::App;
+use crate::recording::Recording;
+use crate::ui::{self, Event};
+
+/// Terminal size used when a recording has no resize event.
+pub const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// An asciinema v2 cast.
+    Asciicast,
+    /// The screens as plain text.
+    Frames,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "asciicast" | "cast" => Ok(ExportFormat::Asciicast),
+            "frames" | "text" => Ok(ExportFormat::Frames),
+            _ => Err(format!("Unknown format '{}': use asciicast or frames", s)),
+        }
+    }
+}
+
+/// One screen of a replayed recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Milliseconds since the recording started.
+    pub at: u64,
+    pub screen: Buffer,
+}
+
+impl Frame {
+    /// The screen as plain text, one line per row with trailing spaces
+    /// trimmed.
+    pub fn text(&self) -> String {
+        let area = self.screen.area;
+        let mut text = String::new();
+        for y in area.top()..area.bottom() {
+            let mut row = String::new();
+            let mut x = area.left();
+            while x < area.right() {
+                let symbol = self.screen[(x, y)].symbol();
+                row.push_str(symbol);
+                x += symbol.width().max(1) as u16;
+            }
+            text.push_str(row.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Escape sequences that draw the whole screen, with colors, over what
+    /// the terminal showed before.
+    pub fn ansi(&self) -> String {
+        let area = self.screen.area;
+        let mut out = String::new();
+        let mut style = None;
+
+        for y in area.top()..area.bottom() {
+            out.push_str(&format!("\x1b[{};1H", y - area.top() + 1));
+            let mut x = area.left();
+            while x < area.right() {
+                let cell = &self.screen[(x, y)];
+                let cell_style = cell.style();
+                if style != Some(cell_style) {
+                    out.push_str(&sgr(cell_style));
+                    style = Some(cell_style);
+                }
+                let symbol = cell.symbol();
+                out.push_str(symbol);
+                x += symbol.width().max(1) as u16;
+            }
+        }
+        out.push_str("\x1b[0m");
+        out
+    }
+}
+
+/// An SGR sequence that resets the attributes and then sets `style`.
+fn sgr(style: Style) -> String {
+    let mut codes = vec!["0".to_string()];
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in modifiers {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+    if let Some(code) = style.fg.and_then(|c| color_code(c, false)) {
+        codes.push(code);
+    }
+    if let Some(code) = style.bg.and_then(|c| color_code(c, true)) {
+        codes.push(code);
+    }
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+fn color_code(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+        Color::Indexed(index) => return Some(format!("{};5;{}", base + 8, index)),
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    };
+    Some(code.to_string())
+}
+
+/// Replays `recording` into `app` and renders a frame at the start and after
+/// every event that changes the screen. `app` should be set up the way the
+/// recorded session started; the replay stops at the recorded quit. The
+/// app's clock follows the recorded times, so mappings time out where they
+/// did in the session.
+pub fn render_frames(app: &mut App, recording: &Recording) -> Vec<Frame> {
+    let start = app.clock;
+    let since_start = |now: Instant| now.duration_since(start).as_millis() as u64;
+    let (width, height) = recording
+        .events
+        .iter()
+        .find_map(|recorded| match recorded.event {
+            Event::Resize(width, height) => Some((width, height)),
+            _ => None,
+        })
+        .unwrap_or(DEFAULT_SIZE);
+    app.handle_event(Event::Resize(width, height));
+
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("the test backend cannot fail");
+    let mut frames = vec![Frame {
+        at: 0,
+        screen: draw(&mut terminal, app),
+    }];
+
+    for recorded in &recording.events {
+        let now = start + Duration::from_millis(recorded.at);
+        if let Some(deadline) = app.mapper.deadline().filter(|&deadline| deadline <= now) {
+            app.tick(deadline);
+            push_frame(&mut frames, &mut terminal, app, since_start(deadline));
+        }
+        app.tick(now);
+
+        match recorded.event {
+            Event::Quit => break,
+            Event::Resize(width, height) => terminal.backend_mut().resize(width, height),
+            _ => {}
+        }
+        app.handle_event(recorded.event.clone());
+        if !app.is_running() {
+            break;
+        }
+        push_frame(&mut frames, &mut terminal, app, recorded.at);
+    }
+
+    frames
+}
+
+/// Adds a frame at `at` if the screen changed since the last one.
+fn push_frame(frames: &mut Vec<Frame>, terminal: &mut Terminal<TestBackend>, app: &App, at: u64) {
+    let screen = draw(terminal, app);
+    if frames.last().is_some_and(|frame| frame.screen != screen) {
+        frames.push(Frame { at, screen });
+    }
+}
+
+fn draw(terminal: &mut Terminal<TestBackend>, app: &App) -> Buffer {
+    terminal
+        .draw(|frame| ui::render_ui(frame, app))
+        .expect("the test backend cannot fail");
+    terminal.backend().buffer().clone()
+}
+
+/// An asciinema v2 cast of `frames`: a JSON header line, then one output
+/// event per frame and a resize event wherever the size changes.
+pub fn asciicast(frames: &[Frame], title: &str) -> String {
+    let Some(first) = frames.first() else {
+        return String::new();
+    };
+    let header = json!({
+        "version": 2,
+        "width": first.screen.area.width,
+        "height": first.screen.area.height,
+        "title": title,
+        "env": { "TERM": "xterm-256color" },
+    });
+
+    let mut cast = format!("{}\n", header);
+    let mut area = first.screen.area;
+    for frame in frames {
+        let time = frame.at as f64 / 1000.0;
+        let mut output = String::new();
+        if frame.screen.area != area {
+            area = frame.screen.area;
+            let size = format!("{}x{}", area.width, area.height);
+            cast.push_str(&format!("{}\n", json!([time, "r", size])));
+            output.push_str("\x1b[2J");
+        }
+        output.push_str(&frame.ansi());
+        cast.push_str(&format!("{}\n", json!([time, "o", output])));
+    }
+    cast
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::recording::RecordedEvent;
+
+    const SAMPLE: &str = "vex-recording 1 7 []\n\
+                          0 resize 60 18\n\
+                          400 key 1\n\
+                          300 key x\n\
+                          300 key l\n\
+                          500 resize 80 24\n\
+                          200 quit\n\
+                          100 key q\n";
+
+    #[test]
+    fn test_render_frames() {
+        let recording = Recording::parse(SAMPLE.as_bytes()).unwrap();
+        let frames = render_frames(&mut App::new(), &recording);
+
+        // The menu, the lesson, the invalid key's feedback, the solved task
+        // and the resized screen; the events after the quit are ignored.
+        let times: Vec<u64> = frames.iter().map(|f| f.at).collect();
+        assert_eq!(times, vec![0, 400, 700, 1000, 1500]);
+        assert!(frames[0].text().contains("Basic Movement"));
+        assert!(frames[3].text().contains("Correct!"));
+        assert_eq!(frames[3].text().lines().count(), 18);
+        assert_eq!(frames[4].screen.area.width, 80);
+    }
+
+    #[test]
+    fn test_mapping_timeout_follows_the_recording() {
+        let config = Config::parse("[mappings]\nmap = [\"nnoremap g h\", \"nnoremap gl l\"]").unwrap();
+        let text = format!(
+            "vex-recording 1 7 []\nconfig {}\n0 resize 80 24\n400 key 1\n300 key g\n1500 key l\n",
+            serde_json::to_string(&config).unwrap()
+        );
+        let recording = Recording::parse(text.as_bytes()).unwrap();
+        let mut app = recording.app().unwrap();
+        let frames = render_frames(&mut app, &recording);
+
+        // The g is shown waiting for "gl" and becomes h once timeoutlen has
+        // passed, before the l arrives, as it did in the recorded session.
+        let times: Vec<u64> = frames.iter().map(|f| f.at).collect();
+        assert_eq!(times, vec![0, 400, 700, 1700, 2200]);
+
+        let mut live = App::new();
+        live.apply_config(&config).unwrap();
+        live.send_keys("1g");
+        live.send_keys("l");
+        assert_eq!(app.executed_commands, live.executed_commands);
+        assert_eq!(app.feedback_message, live.feedback_message);
+        assert_eq!(app.task_mistakes, live.task_mistakes);
+    }
+
+    #[test]
+    fn test_challenge_times_follow_the_recording() {
+        let header = "vex-recording 1 9 [\"challenge\",\"hjkl-sprint\"]\n";
+        let mut recording = Recording::parse(header.as_bytes()).unwrap();
+        let mut app = recording.app().unwrap();
+
+        // Every key of the optimal solutions, 300ms apart.
+        let tasks = &app.session.as_ref().unwrap().tasks;
+        let motions = tasks
+            .iter()
+            .flat_map(|t| t.task.expected_commands.iter().filter_map(|c| c.as_motion()));
+        for (index, motion) in motions.enumerate() {
+            let key = motion.to_key_str().parse().unwrap();
+            let at = 300 * (index as u64 + 1);
+            recording.events.push(RecordedEvent { at, event: Event::Key(key) });
+        }
+        let total = recording.duration();
+        let frames = render_frames(&mut app, &recording);
+
+        assert!(frames[1].text().contains("⏱ 0.3s"));
+        let summary = app.summary.unwrap();
+        assert_eq!(summary.total_time, Duration::from_millis(total));
+        let last = frames.last().unwrap().text();
+        assert!(last.contains(&format!("{:.1}s", total as f64 / 1000.0)));
+    }
+
+    #[test]
+    fn test_asciicast() {
+        let recording = Recording::parse(SAMPLE.as_bytes()).unwrap();
+        let frames = render_frames(&mut App::new(), &recording);
+        let cast = asciicast(&frames, "vex");
+        let lines: Vec<serde_json::Value> = cast
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(lines[0]["width"], 60);
+        assert_eq!(lines[0]["height"], 18);
+        assert_eq!(lines.len(), 1 + frames.len() + 1);
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[5], json!([1.5, "r", "80x24"]));
+        assert!(lines[6][2].as_str().unwrap().starts_with("\x1b[2J\x1b[1;1H"));
+    }
+
+    #[test]
+    fn test_sgr() {
+        assert_eq!(sgr(Style::default()), "\x1b[0m");
+        let style = Style::default()
+            .fg(Color::Black)
+            .bg(Color::LightCyan)
+            .add_modifier(Modifier::BOLD);
+        assert_eq!(sgr(style), "\x1b[0;1;30;106m");
+        assert_eq!(color_code(Color::Indexed(208), false).unwrap(), "38;5;208");
+    }
+}
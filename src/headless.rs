@@ -0,0 +1,191 @@
+//! Scripted lesson playthroughs without a terminal, as used by `vex run`.
+
+use serde::Serialize;
+
+use crate::app::{App, AppMode};
+use crate::ui::Event;
+use crate::vim::{CursorPosition, Key};
+
+/// What happened to one task of a scripted run. Positions are 0-based.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaskReport {
+    pub task: usize,
+    pub description: String,
+    /// Whether the keys got as far as this task.
+    pub reached: bool,
+    pub solved: bool,
+    /// Keys sent while this was the current task, in Vim notation.
+    pub keys: String,
+    /// The last feedback shown for this task.
+    pub feedback: String,
+    pub cursor: CursorPosition,
+    pub target: CursorPosition,
+    pub mistakes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunReport {
+    pub lesson: usize,
+    pub title: String,
+    /// Every task was solved.
+    pub completed: bool,
+    pub tasks: Vec<TaskReport>,
+    /// Keys left over after the lesson ended or was left.
+    pub unused_keys: String,
+}
+
+/// Plays lesson `lesson_id` by sending `keys` to an `App`, one event per key.
+/// The lesson is started with [`App::start_lesson`], so the keys begin at its
+/// first task rather than in the menu.
+///
+/// ```
+/// use vex::headless::run_lesson;
+/// use vex::vim::Key;
+///
+/// let report = run_lesson(1, &Key::parse_sequence("l<Space>j")).unwrap();
+/// assert!(report.tasks[0].solved);
+/// assert!(report.tasks[1].solved);
+/// assert!(!report.tasks[2].reached);
+/// ```
+pub fn run_lesson(lesson_id: usize, keys: &[Key]) -> Result<RunReport, String> {
+    let mut app = App::new();
+    let lesson = app
+        .curriculum
+        .get_lesson(lesson_id)
+        .ok_or_else(|| format!("Unknown lesson {}", lesson_id))?;
+    let title = lesson.title.clone();
+    let mut tasks: Vec<TaskReport> = lesson
+        .tasks
+        .iter()
+        .enumerate()
+        .map(|(index, task)| TaskReport {
+            task: index + 1,
+            description: task.description.clone(),
+            reached: false,
+            solved: false,
+            keys: String::new(),
+            feedback: String::new(),
+            cursor: task.initial_buffer.cursor(),
+            target: task.target_position,
+            mistakes: 0,
+        })
+        .collect();
+
+    app.start_lesson(lesson_id);
+    if let Some(first) = tasks.first_mut() {
+        first.reached = true;
+    }
+
+    let mut sent = 0;
+    for &key in keys {
+        if app.mode != AppMode::Lesson {
+            break;
+        }
+
+        let index = app.current_task_index;
+        tasks[index].keys.push_str(&key.to_string());
+        app.handle_event(Event::Key(key));
+        sent += 1;
+        update_reports(&app, &mut tasks, index);
+    }
+
+    // Keys left waiting for a longer mapping take it, as when typing stops.
+    if app.mode == AppMode::Lesson {
+        let index = app.current_task_index;
+        app.wait_for_mappings();
+        update_reports(&app, &mut tasks, index);
+    }
+
+    Ok(RunReport {
+        lesson: lesson_id,
+        title,
+        completed: tasks.iter().all(|t| t.solved),
+        tasks,
+        unused_keys: keys[sent..].iter().map(|k| k.to_string()).collect(),
+    })
+}
+
+/// Updates the reports after a key sent during task `index`. Keys that move
+/// on to the next task (or back to the menu) leave the finished task's
+/// report as it was.
+fn update_reports(app: &App, tasks: &mut [TaskReport], index: usize) {
+    if app.mode == AppMode::Lesson && app.current_task_index == index {
+        let report = &mut tasks[index];
+        report.solved = app.task_completed;
+        report.feedback = app.feedback_message.clone();
+        report.cursor = app.task_buffer.cursor();
+        report.mistakes = app.task_mistakes;
+    } else if app.mode == AppMode::Lesson {
+        tasks[app.current_task_index].reached = true;
+    }
+}
+
+/// The keys of a keys file for `vex run`: line breaks are dropped (use
+/// `<CR>` for Enter) and lines starting with `#` are skipped.
+pub fn read_keys(contents: &str) -> String {
+    contents
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learning::Curriculum;
+    use crate::vim::Motion;
+
+    /// Keys that solve a task the intended way: the expected commands, or
+    /// for quizzes, walking the guess marker to the answer and confirming.
+    fn solution_keys(task: &crate::learning::Task) -> String {
+        if !task.is_prediction() {
+            return task
+                .expected_commands
+                .iter()
+                .filter_map(|c| c.as_motion())
+                .map(Motion::to_key_str)
+                .collect();
+        }
+
+        let start = task.initial_buffer.cursor();
+        let target = task.target_position;
+        let vertical = if target.line >= start.line { "j" } else { "k" };
+        let horizontal = if target.column >= start.column { "l" } else { "h" };
+        format!(
+            "{}{}<CR>",
+            vertical.repeat(target.line.abs_diff(start.line)),
+            horizontal.repeat(target.column.abs_diff(start.column))
+        )
+    }
+
+    #[test]
+    fn test_every_lesson_is_solvable() {
+        for lesson in Curriculum::new().lessons() {
+            let script: Vec<String> = lesson.tasks.iter().map(solution_keys).collect();
+            let keys = Key::parse_sequence(&script.join("<Space>"));
+            let report = run_lesson(lesson.id, &keys).unwrap();
+
+            assert!(report.completed, "lesson {}: {:#?}", lesson.id, report.tasks);
+            assert!(report.tasks.iter().all(|t| t.mistakes == 0));
+            assert_eq!(report.unused_keys, "");
+        }
+    }
+
+    #[test]
+    fn test_report_tracks_each_task() {
+        let report = run_lesson(1, &Key::parse_sequence("l<Space>k<Esc>j")).unwrap();
+
+        assert_eq!(report.tasks[0].keys, "l<Space>");
+        assert!(report.tasks[0].solved);
+        assert_eq!(report.tasks[1].keys, "k<Esc>");
+        assert!(!report.tasks[1].solved);
+        assert_eq!(report.unused_keys, "j");
+        assert!(!report.completed);
+        assert!(run_lesson(99, &[]).is_err());
+    }
+
+    #[test]
+    fn test_read_keys() {
+        assert_eq!(read_keys("# lesson 1\nl<Space>\n  # next\nj\n"), "l<Space>j");
+    }
+}
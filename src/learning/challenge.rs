@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use super::{Curriculum, Difficulty, DrillGenerator, Task, TaskCheck};
+use crate::vim::{Command, CommandParser, Motion, Options};
+
+/// A learner may use up to this many times the optimal number of commands
+/// before a challenge task counts as failed.
+pub const MAX_COMMAND_FACTOR: usize = 3;
+
+/// A named, timed run of generated tasks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Challenge {
+    pub name: &'static str,
+    pub title: &'static str,
+    pub keys: &'static str,
+    pub difficulty: Difficulty,
+    pub task_count: usize,
+}
+
+impl Challenge {
+    pub fn builtin() -> Vec<Challenge> {
+        vec![
+            Challenge {
+                name: "hjkl-sprint",
+                title: "hjkl Sprint",
+                keys: "hjkl",
+                difficulty: Difficulty::Easy,
+                task_count: 10,
+            },
+            Challenge {
+                name: "word-sprint",
+                title: "Word Sprint",
+                keys: "wbe",
+                difficulty: Difficulty::Medium,
+                task_count: 10,
+            },
+            Challenge {
+                name: "line-sprint",
+                title: "Line Sprint",
+                keys: "wbe0$",
+                difficulty: Difficulty::Medium,
+                task_count: 10,
+            },
+            Challenge {
+                name: "full-sprint",
+                title: "Full Sprint",
+                keys: "hjklwbe0$ggG",
+                difficulty: Difficulty::Hard,
+                task_count: 15,
+            },
+        ]
+    }
+
+    pub fn find(name: &str) -> Option<Challenge> {
+        Self::builtin().into_iter().find(|c| c.name == name)
+    }
+
+    pub fn motions(&self) -> Vec<Motion> {
+        CommandParser::parse_motions(self.keys).unwrap_or_default()
+    }
+
+    /// Generates the challenge's tasks. Any route to the target is accepted,
+    /// so keystroke efficiency can be scored separately.
+    pub fn generate_tasks(&self, curriculum: &Curriculum, seed: u64, options: &Options) -> Vec<Task> {
+        let motions = self.motions();
+        let mut generator = DrillGenerator::new(
+            motions.clone(),
+            DrillGenerator::default_corpus(curriculum),
+            self.difficulty,
+            seed,
+        )
+        .with_options(options);
+
+        (0..self.task_count)
+            .map_while(|_| generator.next_task())
+            .map(|task| {
+                let max_commands = task.expected_commands.len() * MAX_COMMAND_FACTOR;
+                task.with_check(TaskCheck::ReachTarget {
+                    allowed: motions.clone(),
+                    max_commands,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Number of keys needed to type the given commands.
+pub fn keystroke_count(commands: &[Command]) -> usize {
+    commands
+        .iter()
+        .filter_map(|c| c.as_motion())
+        .map(|m| m.to_key_str().len())
+        .sum()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeTaskResult {
+    pub solved: bool,
+    pub elapsed: Duration,
+    pub keystrokes: usize,
+    pub optimal_keystrokes: usize,
+    pub motions: Vec<Motion>,
+}
+
+impl ChallengeTaskResult {
+    /// Points out of 100: keystroke efficiency scaled by speed against a par
+    /// time of one second plus half a second per optimal keystroke.
+    pub fn points(&self) -> u32 {
+        if !self.solved || self.keystrokes == 0 {
+            return 0;
+        }
+
+        let efficiency = (self.optimal_keystrokes as f64 / self.keystrokes as f64).min(1.0);
+        let par_ms = 1000.0 + 500.0 * self.optimal_keystrokes as f64;
+        let elapsed_ms = self.elapsed.as_millis().max(1) as f64;
+        let speed = (par_ms / elapsed_ms).clamp(0.25, 1.0);
+
+        (100.0 * efficiency * speed).round() as u32
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChallengeSummary {
+    pub challenge: String,
+    pub tasks: usize,
+    pub solved: usize,
+    pub score: u32,
+    pub total_time: Duration,
+    pub average_time: Duration,
+    pub efficiency: f64,
+    /// Motions ordered by the average time of the tasks that needed them, slowest first.
+    pub slowest_motions: Vec<(Motion, Duration)>,
+    pub new_best: bool,
+}
+
+impl ChallengeSummary {
+    pub fn new(challenge: &str, results: &[ChallengeTaskResult]) -> Self {
+        let tasks = results.len();
+        let solved = results.iter().filter(|r| r.solved).count();
+        let total_time: Duration = results.iter().map(|r| r.elapsed).sum();
+        let average_time = if tasks > 0 {
+            total_time / tasks as u32
+        } else {
+            Duration::ZERO
+        };
+
+        let keystrokes: usize = results.iter().filter(|r| r.solved).map(|r| r.keystrokes).sum();
+        let optimal: usize = results
+            .iter()
+            .filter(|r| r.solved)
+            .map(|r| r.optimal_keystrokes)
+            .sum();
+        let efficiency = if keystrokes > 0 {
+            optimal as f64 / keystrokes as f64
+        } else {
+            0.0
+        };
+
+        let mut per_motion: Vec<(Motion, Duration, u32)> = Vec::new();
+        for result in results {
+            let mut seen: Vec<Motion> = Vec::new();
+            for &motion in &result.motions {
+                if seen.contains(&motion) {
+                    continue;
+                }
+                seen.push(motion);
+
+                match per_motion.iter_mut().find(|(m, _, _)| *m == motion) {
+                    Some(entry) => {
+                        entry.1 += result.elapsed;
+                        entry.2 += 1;
+                    }
+                    None => per_motion.push((motion, result.elapsed, 1)),
+                }
+            }
+        }
+
+        let mut slowest_motions: Vec<(Motion, Duration)> = per_motion
+            .into_iter()
+            .map(|(motion, total, count)| (motion, total / count))
+            .collect();
+        slowest_motions.sort_by_key(|&(_, average)| std::cmp::Reverse(average));
+
+        Self {
+            challenge: challenge.to_string(),
+            tasks,
+            solved,
+            score: results.iter().map(|r| r.points()).sum(),
+            total_time,
+            average_time,
+            efficiency,
+            slowest_motions,
+            new_best: false,
+        }
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.tasks == 0 {
+            0.0
+        } else {
+            self.solved as f64 / self.tasks as f64
+        }
+    }
+}
+
+/// A challenge in progress.
+pub struct ChallengeRun {
+    pub challenge: Challenge,
+    pub started_at: Instant,
+    pub results: Vec<ChallengeTaskResult>,
+}
+
+impl ChallengeRun {
+    /// Starts the run at `started_at`, a time on the app's clock.
+    pub fn new(challenge: Challenge, started_at: Instant) -> Self {
+        Self {
+            challenge,
+            started_at,
+            results: Vec::new(),
+        }
+    }
+
+    pub fn summary(&self) -> ChallengeSummary {
+        ChallengeSummary::new(self.challenge.name, &self.results)
+    }
+}
+
+/// The best run of a challenge, kept in the progress store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersonalBest {
+    pub score: u32,
+    pub accuracy: f64,
+    pub average_time_ms: u64,
+    pub efficiency: f64,
+    pub achieved: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(solved: bool, ms: u64, keystrokes: usize, optimal: usize, motions: &[Motion]) -> ChallengeTaskResult {
+        ChallengeTaskResult {
+            solved,
+            elapsed: Duration::from_millis(ms),
+            keystrokes,
+            optimal_keystrokes: optimal,
+            motions: motions.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_points() {
+        assert_eq!(result(true, 1000, 2, 2, &[]).points(), 100);
+        assert_eq!(result(true, 1000, 4, 2, &[]).points(), 50);
+        assert_eq!(result(true, 4000, 2, 2, &[]).points(), 50);
+        assert_eq!(result(false, 1000, 2, 2, &[]).points(), 0);
+    }
+
+    #[test]
+    fn test_summary() {
+        let results = vec![
+            result(true, 1000, 2, 2, &[Motion::WordForward]),
+            result(true, 3000, 4, 2, &[Motion::WordEnd, Motion::WordForward]),
+            result(false, 8000, 6, 2, &[Motion::LineEnd]),
+        ];
+        let summary = ChallengeSummary::new("word-sprint", &results);
+
+        assert_eq!(summary.tasks, 3);
+        assert_eq!(summary.solved, 2);
+        assert!((summary.accuracy() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.average_time, Duration::from_millis(4000));
+        assert!((summary.efficiency - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(summary.slowest_motions[0], (Motion::LineEnd, Duration::from_millis(8000)));
+        assert_eq!(summary.slowest_motions[1], (Motion::WordEnd, Duration::from_millis(3000)));
+        assert_eq!(summary.slowest_motions[2], (Motion::WordForward, Duration::from_millis(2000)));
+    }
+
+    #[test]
+    fn test_builtin_challenges_generate_tasks() {
+        let curriculum = Curriculum::new();
+        for challenge in Challenge::builtin() {
+            assert!(!challenge.motions().is_empty());
+            let tasks = challenge.generate_tasks(&curriculum, 5, &Options::default());
+            assert_eq!(tasks.len(), challenge.task_count, "{}", challenge.name);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{Lesson, Task};
+use super::{Language, Lesson, Task};
 use crate::vim::{Buffer, Command, CursorPosition, Motion};
 
 pub struct Curriculum {
@@ -15,6 +15,7 @@ impl Curriculum {
                 Self::lesson_4_combining_movements(),
                 Self::lesson_5_vertical_movement(),
                 Self::lesson_6_complete_practice(),
+                Self::lesson_7_where_does_it_land(),
             ],
         }
     }
@@ -27,6 +28,26 @@ impl Curriculum {
         self.lessons.iter().find(|l| l.id == id)
     }
 
+    /// Position of a lesson within the curriculum.
+    pub fn position(&self, id: usize) -> Option<usize> {
+        self.lessons.iter().position(|l| l.id == id)
+    }
+
+    /// Lessons grouped by section, in curriculum order. A section appears once,
+    /// where its first lesson is.
+    pub fn sections(&self) -> Vec<(&str, Vec<&Lesson>)> {
+        let mut sections: Vec<(&str, Vec<&Lesson>)> = Vec::new();
+
+        for lesson in &self.lessons {
+            match sections.iter_mut().find(|(name, _)| *name == lesson.section) {
+                Some((_, lessons)) => lessons.push(lesson),
+                None => sections.push((&lesson.section, vec![lesson])),
+            }
+        }
+
+        sections
+    }
+
     fn lesson_1_basic_movement() -> Lesson {
         let explanation = vec![
             "Welcome to VEX! Let's start with the foundation of Vim movement.".to_string(),
@@ -58,7 +79,7 @@ impl Curriculum {
                 vec![
                     "Use the 'l' key to move right.".to_string(),
                     "Press 'l' once.".to_string(),
-                    "Answer: l".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -69,7 +90,7 @@ impl Curriculum {
                 vec![
                     "Use the 'j' key to move down.".to_string(),
                     "Press 'j' once.".to_string(),
-                    "Answer: j".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -84,7 +105,7 @@ impl Curriculum {
                 vec![
                     "Press 'l' three times.".to_string(),
                     "Each 'l' moves right by one character.".to_string(),
-                    "Answer: lll".to_string(),
+                    "It can be done in 3 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -99,7 +120,7 @@ impl Curriculum {
                 vec![
                     "You need to move down from line one to line two.".to_string(),
                     "Use 'j' to move down one line.".to_string(),
-                    "Answer: j".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -114,7 +135,7 @@ impl Curriculum {
                 vec![
                     "Use the 'h' key to move left.".to_string(),
                     "Press 'h' once.".to_string(),
-                    "Answer: h".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -129,12 +150,12 @@ impl Curriculum {
                 vec![
                     "Use the 'k' key to move up.".to_string(),
                     "Press 'k' once.".to_string(),
-                    "Answer: k".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
         ];
 
-        Lesson::new(1, "Basic Movement (hjkl)", explanation, commands, tasks)
+        Lesson::new(1, "Basic Movement (hjkl)", explanation, commands, tasks).in_section("Fundamentals")
     }
 
     fn lesson_2_word_movement() -> Lesson {
@@ -166,7 +187,7 @@ impl Curriculum {
                 vec![
                     "Use 'w' to jump to the next word.".to_string(),
                     "Press 'w' once to move from 'The' to 'quick'.".to_string(),
-                    "Answer: w".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -181,7 +202,7 @@ impl Curriculum {
                 vec![
                     "You need to move forward three words.".to_string(),
                     "Press 'w' three times: The→quick→brown→fox".to_string(),
-                    "Answer: www".to_string(),
+                    "It can be done in 3 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -196,7 +217,7 @@ impl Curriculum {
                 vec![
                     "Use 'e' to jump to the end of a word.".to_string(),
                     "Press 'e' once to move to the end of 'brown'.".to_string(),
-                    "Answer: e".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -214,12 +235,12 @@ impl Curriculum {
                 vec![
                     "Use 'b' to move backward by words.".to_string(),
                     "Press 'b' twice: fox→brown→quick".to_string(),
-                    "Answer: bb".to_string(),
+                    "It can be done in 2 command(s).".to_string(),
                 ],
             ),
         ];
 
-        Lesson::new(2, "Word Movement (wbe)", explanation, commands, tasks)
+        Lesson::new(2, "Word Movement (wbe)", explanation, commands, tasks).in_section("Fundamentals")
     }
 
     fn lesson_3_line_extremes() -> Lesson {
@@ -253,7 +274,7 @@ impl Curriculum {
                 vec![
                     "Use '0' to jump to the start of the line.".to_string(),
                     "Press '0' (zero) once.".to_string(),
-                    "Answer: 0".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -264,7 +285,7 @@ impl Curriculum {
                 vec![
                     "Use '$' to jump to the end of the line.".to_string(),
                     "Press '$' once.".to_string(),
-                    "Answer: $".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -282,12 +303,12 @@ impl Curriculum {
                 vec![
                     "First jump to the end with '$', then to the start with '0'.".to_string(),
                     "Press '$' then '0'.".to_string(),
-                    "Answer: $0".to_string(),
+                    "It can be done in 2 command(s).".to_string(),
                 ],
             ),
         ];
 
-        Lesson::new(3, "Line Extremes (0$)", explanation, commands, tasks)
+        Lesson::new(3, "Line Extremes (0$)", explanation, commands, tasks).in_section("Fundamentals")
     }
 
     fn lesson_4_combining_movements() -> Lesson {
@@ -331,7 +352,7 @@ impl Curriculum {
                 vec![
                     "Count the words: The quick brown fox jumps over the lazy".to_string(),
                     "That's 7 words forward. Use 'w' seven times.".to_string(),
-                    "Answer: wwwwwww".to_string(),
+                    "It can be done in 7 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -347,7 +368,7 @@ impl Curriculum {
                 vec![
                     "First use '$' to reach the end.".to_string(),
                     "Then use 'b' three times to go back.".to_string(),
-                    "Answer: $bbb".to_string(),
+                    "It can be done in 4 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -366,7 +387,7 @@ impl Curriculum {
                 vec![
                     "Jump to start with '0', then forward to 'brown'.".to_string(),
                     "Use '0' then 'w' twice.".to_string(),
-                    "Answer: 0ww".to_string(),
+                    "It can be done in 3 command(s).".to_string(),
                 ],
             ),
         ];
@@ -378,6 +399,7 @@ impl Curriculum {
             commands,
             tasks,
         )
+        .in_section("Navigation")
     }
 
     fn lesson_5_vertical_movement() -> Lesson {
@@ -410,7 +432,7 @@ impl Curriculum {
                 vec![
                     "Use 'G' (capital G) to jump to the last line.".to_string(),
                     "Press 'G' once.".to_string(),
-                    "Answer: G".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -425,7 +447,7 @@ impl Curriculum {
                 vec![
                     "Use 'gg' to jump to the first line.".to_string(),
                     "Press 'g' twice quickly.".to_string(),
-                    "Answer: gg".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -443,12 +465,12 @@ impl Curriculum {
                 vec![
                     "First jump to the end with 'G', then back to start with 'gg'.".to_string(),
                     "Press 'G' then 'gg'.".to_string(),
-                    "Answer: Ggg".to_string(),
+                    "It can be done in 2 command(s).".to_string(),
                 ],
             ),
         ];
 
-        Lesson::new(5, "Vertical Movement (gg, G)", explanation, commands, tasks)
+        Lesson::new(5, "Vertical Movement (gg, G)", explanation, commands, tasks).in_section("Navigation")
     }
 
     fn lesson_6_complete_practice() -> Lesson {
@@ -497,7 +519,7 @@ impl Curriculum {
                 vec![
                     "From the start, move forward one word.".to_string(),
                     "Use 'w' once.".to_string(),
-                    "Answer: w".to_string(),
+                    "It can be done in 1 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -513,7 +535,7 @@ impl Curriculum {
                 vec![
                     "First jump to the last line with 'G'.".to_string(),
                     "Then move up with 'k' and forward two words with 'w'.".to_string(),
-                    "Answer: Gkww".to_string(),
+                    "It can be done in 4 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -531,7 +553,7 @@ impl Curriculum {
                 vec![
                     "Move up one line, then to the end of the word.".to_string(),
                     "Use 'k' to move up, then 'e' to jump to word end.".to_string(),
-                    "Answer: ke".to_string(),
+                    "It can be done in 2 command(s).".to_string(),
                 ],
             ),
             Task::new(
@@ -549,7 +571,7 @@ impl Curriculum {
                 vec![
                     "Jump to the first line, then to the end of that line.".to_string(),
                     "Use 'gg' then '$'.".to_string(),
-                    "Answer: gg$".to_string(),
+                    "It can be done in 2 command(s).".to_string(),
                 ],
             ),
         ];
@@ -561,6 +583,79 @@ impl Curriculum {
             commands,
             tasks,
         )
+        .in_section("Navigation")
+        .in_language(Language::JavaScript)
+    }
+
+    fn lesson_7_where_does_it_land() -> Lesson {
+        let explanation = vec![
+            "Reading motions is as important as typing them.".to_string(),
+            "".to_string(),
+            "Each task shows a few keys. Predict where they leave the cursor:".to_string(),
+            "  - click that cell in the buffer, or".to_string(),
+            "  - move the guess marker with h, j, k, l and press Enter.".to_string(),
+            "".to_string(),
+            "The cursor does not move, so you have to work it out in your head.".to_string(),
+        ];
+
+        let commands = vec![
+            Command::Motion(Motion::WordForward),
+            Command::Motion(Motion::WordEnd),
+            Command::Motion(Motion::Down),
+            Command::Motion(Motion::LineEnd),
+            Command::Motion(Motion::FileEnd),
+            Command::Motion(Motion::WordBackward),
+        ];
+
+        let buffer_text = "let total = price * quantity;\n\
+                           if total > limit {\n\
+                           warn(total);\n\
+                           }";
+
+        let tasks = vec![
+            Task::prediction(
+                "Where does 'ww' land?",
+                Buffer::new(buffer_text),
+                vec![Command::Motion(Motion::WordForward), Command::Motion(Motion::WordForward)],
+                vec![
+                    "The first 'w' reaches 'total'.".to_string(),
+                    "Punctuation such as '=' is a word of its own.".to_string(),
+                    "It stays on the first line.".to_string(),
+                ],
+            ),
+            Task::prediction(
+                "Where does 'ee' land?",
+                Buffer::new(buffer_text),
+                vec![Command::Motion(Motion::WordEnd), Command::Motion(Motion::WordEnd)],
+                vec![
+                    "The first 'e' reaches the end of 'let'.".to_string(),
+                    "The second goes to the end of the next word.".to_string(),
+                    "It stays on the first line too.".to_string(),
+                ],
+            ),
+            Task::prediction(
+                "Where does 'j$' land?",
+                Buffer::new(buffer_text),
+                vec![Command::Motion(Motion::Down), Command::Motion(Motion::LineEnd)],
+                vec![
+                    "'j' moves to the second line.".to_string(),
+                    "'$' goes to that line's last character.".to_string(),
+                    "It lands on a punctuation character.".to_string(),
+                ],
+            ),
+            Task::prediction(
+                "Where does 'Gb' land?",
+                Buffer::new(buffer_text),
+                vec![Command::Motion(Motion::FileEnd), Command::Motion(Motion::WordBackward)],
+                vec![
+                    "'G' moves to the last line, which holds only '}'.".to_string(),
+                    "'b' crosses onto the line above.".to_string(),
+                    "A run of punctuation counts as one word.".to_string(),
+                ],
+            ),
+        ];
+
+        Lesson::new(7, "Where Does It Land?", explanation, commands, tasks).in_section("Quizzes")
     }
 }
 
@@ -0,0 +1,431 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use super::{Curriculum, Language, Task, TaskCheck};
+use crate::vim::{Buffer, Command, CursorPosition, Executor, Motion, Options};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    /// The range of optimal solution lengths a generated task may have.
+    pub fn solution_length(self) -> (usize, usize) {
+        match self {
+            Difficulty::Easy => (1, 2),
+            Difficulty::Medium => (2, 4),
+            Difficulty::Hard => (4, 7),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("Unknown difficulty '{}': use easy, medium or hard", s)),
+        }
+    }
+}
+
+/// Shortest command sequences from one cursor position to every position
+/// reachable within `max_depth` commands using only `motions`.
+pub fn shortest_paths(
+    buffer: &Buffer,
+    motions: &[Motion],
+    max_depth: usize,
+) -> HashMap<CursorPosition, Vec<Motion>> {
+    let start = buffer.cursor();
+    let mut paths: HashMap<CursorPosition, Vec<Motion>> = HashMap::new();
+    paths.insert(start, Vec::new());
+
+    let mut queue = VecDeque::from([start]);
+    let mut probe = buffer.clone();
+
+    while let Some(position) = queue.pop_front() {
+        let path = paths[&position].clone();
+        if path.len() >= max_depth {
+            continue;
+        }
+
+        for &motion in motions {
+            probe.set_cursor(position);
+            if Executor::execute(&mut probe, Command::Motion(motion)).is_err() {
+                continue;
+            }
+
+            let next = probe.cursor();
+            if let Entry::Vacant(entry) = paths.entry(next) {
+                let mut next_path = path.clone();
+                next_path.push(motion);
+                entry.insert(next_path);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    paths
+}
+
+/// The kinds of positions a generated task may target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    /// The first character of an identifier.
+    Identifier,
+    /// An opening or closing bracket.
+    Bracket,
+    /// The last character of a line.
+    LineEnd,
+}
+
+impl TargetKind {
+    pub fn matches(self, buffer: &Buffer, position: CursorPosition) -> bool {
+        let Some(line) = buffer.get_line(position.line) else {
+            return false;
+        };
+        let chars: Vec<char> = line.chars().collect();
+        let Some(&ch) = chars.get(position.column) else {
+            return false;
+        };
+
+        match self {
+            TargetKind::Identifier => {
+                let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+                (ch.is_alphabetic() || ch == '_')
+                    && (position.column == 0 || !is_ident(chars[position.column - 1]))
+            }
+            TargetKind::Bracket => "()[]{}<>".contains(ch),
+            TargetKind::LineEnd => position.column + 1 == chars.len(),
+        }
+    }
+}
+
+impl std::str::FromStr for TargetKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "identifiers" | "identifier" => Ok(TargetKind::Identifier),
+            "brackets" | "bracket" => Ok(TargetKind::Bracket),
+            "line-ends" | "line-end" => Ok(TargetKind::LineEnd),
+            _ => Err(format!(
+                "Unknown target '{}': use identifiers, brackets or line-ends",
+                s
+            )),
+        }
+    }
+}
+
+fn is_basic(motion: Motion) -> bool {
+    matches!(motion, Motion::Left | Motion::Right | Motion::Up | Motion::Down)
+}
+
+/// Produces random drill tasks whose optimal solutions use a given set of motions.
+pub struct DrillGenerator {
+    motions: Vec<Motion>,
+    corpus: Vec<(Buffer, Language)>,
+    difficulty: Difficulty,
+    targets: Vec<TargetKind>,
+    seed: u64,
+    rng: StdRng,
+}
+
+impl DrillGenerator {
+    /// Draws tasks from `corpus`, each buffer with the language it is written in.
+    pub fn new(
+        motions: Vec<Motion>,
+        corpus: Vec<(Buffer, Language)>,
+        difficulty: Difficulty,
+        seed: u64,
+    ) -> Self {
+        Self {
+            motions,
+            corpus,
+            difficulty,
+            targets: Vec::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Restricts targets to the given kinds. An empty list allows any position.
+    pub fn with_targets(mut self, targets: Vec<TargetKind>) -> Self {
+        self.targets = targets;
+        self
+    }
+
+    /// Generates tasks for motions that follow `options`.
+    pub fn with_options(mut self, options: &Options) -> Self {
+        self.set_options(options);
+        self
+    }
+
+    pub fn set_options(&mut self, options: &Options) {
+        for (buffer, _) in &mut self.corpus {
+            buffer.set_options(options.clone());
+        }
+    }
+
+    /// The buffer texts used by the built-in lessons.
+    pub fn default_corpus(curriculum: &Curriculum) -> Vec<(Buffer, Language)> {
+        let mut texts: Vec<(Vec<String>, Language)> = Vec::new();
+
+        for lesson in curriculum.lessons() {
+            for task in &lesson.tasks {
+                let lines = task.initial_buffer.lines().to_vec();
+                if !texts.iter().any(|(text, _)| *text == lines) {
+                    texts.push((lines, task.language));
+                }
+            }
+        }
+
+        texts
+            .into_iter()
+            .map(|(lines, language)| (Buffer::from_lines(lines), language))
+            .collect()
+    }
+
+    pub fn motions(&self) -> &[Motion] {
+        &self.motions
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Generates the next task, or `None` if no buffer in the corpus has a
+    /// target at the requested difficulty.
+    pub fn next_task(&mut self) -> Option<Task> {
+        const ATTEMPTS: usize = 50;
+
+        if self.motions.is_empty() || self.corpus.is_empty() {
+            return None;
+        }
+
+        for _ in 0..ATTEMPTS {
+            let (mut buffer, language) = self.corpus.choose(&mut self.rng)?.clone();
+            let start = self.random_position(&buffer)?;
+            buffer.set_cursor(start);
+
+            if let Some((target, path)) = self.pick_target(&buffer) {
+                return Some(build_task(buffer, target, path, &self.motions).in_language(language));
+            }
+        }
+
+        None
+    }
+
+    fn random_position(&mut self, buffer: &Buffer) -> Option<CursorPosition> {
+        let non_empty: Vec<usize> = (0..buffer.line_count())
+            .filter(|&i| buffer.get_line(i).is_some_and(|l| !l.is_empty()))
+            .collect();
+        let line = *non_empty.choose(&mut self.rng)?;
+        let len = buffer.get_line(line)?.chars().count();
+
+        Some(CursorPosition::new(line, self.rng.gen_range(0..len)))
+    }
+
+    fn pick_target(&mut self, buffer: &Buffer) -> Option<(CursorPosition, Vec<Motion>)> {
+        let (min_len, max_len) = self.difficulty.solution_length();
+        let paths = shortest_paths(buffer, &self.motions, max_len);
+        let wants_advanced = self.motions.iter().any(|&m| !is_basic(m));
+
+        let mut candidates: Vec<(CursorPosition, Vec<Motion>)> = paths
+            .into_iter()
+            .filter(|(_, path)| path.len() >= min_len)
+            .filter(|(_, path)| !wants_advanced || path.iter().any(|&m| !is_basic(m)))
+            .filter(|(position, _)| {
+                self.targets.is_empty() || self.targets.iter().any(|t| t.matches(buffer, *position))
+            })
+            .collect();
+
+        // Sort so the choice depends only on the seed, not on hash order.
+        candidates.sort_by_key(|(position, _)| (position.line, position.column));
+        candidates.choose(&mut self.rng).cloned()
+    }
+}
+
+fn build_task(buffer: Buffer, target: CursorPosition, path: Vec<Motion>, allowed: &[Motion]) -> Task {
+    let mut used: Vec<&str> = Vec::new();
+    for motion in &path {
+        if !used.contains(&motion.to_key_str()) {
+            used.push(motion.to_key_str());
+        }
+    }
+
+    let hints = vec![
+        format!("Try the motions: {}", used.join(", ")),
+        format!("It can be done in {} command(s).", path.len()),
+        format!("Start with '{}'.", path[0].to_key_str()),
+    ];
+
+    let description = describe_target(&buffer, target);
+    let expected = path.into_iter().map(Command::Motion).collect();
+
+    Task::new(description, buffer, target, expected, hints).with_check(TaskCheck::ShortestPath {
+        allowed: allowed.to_vec(),
+    })
+}
+
+fn describe_target(buffer: &Buffer, target: CursorPosition) -> String {
+    let line = buffer.get_line(target.line).unwrap_or("");
+    let chars: Vec<char> = line.chars().collect();
+    let Some(&ch) = chars.get(target.column) else {
+        return format!("Move to line {}, column {}", target.line + 1, target.column + 1);
+    };
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    if is_word(ch) {
+        let start = (0..target.column)
+            .rev()
+            .take_while(|&i| is_word(chars[i]))
+            .last()
+            .unwrap_or(target.column);
+        let end = (target.column..chars.len())
+            .take_while(|&i| is_word(chars[i]))
+            .last()
+            .unwrap_or(target.column);
+        let word: String = chars[start..=end].iter().collect();
+
+        if word.chars().count() > 1 {
+            return format!(
+                "Move to the '{}' in '{}' on line {}",
+                ch,
+                word,
+                target.line + 1
+            );
+        }
+    }
+
+    format!(
+        "Move to the '{}' on line {}, column {}",
+        ch,
+        target.line + 1,
+        target.column + 1
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Vec<(Buffer, Language)> {
+        vec![
+            (Buffer::new("The quick brown fox jumps over the lazy dog"), Language::PlainText),
+            (Buffer::new("fn main() {\n    let x = 1;\n}"), Language::Rust),
+        ]
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let buffer = Buffer::new("one two three");
+        let paths = shortest_paths(&buffer, &[Motion::WordForward, Motion::Right], 3);
+
+        assert_eq!(paths[&CursorPosition::new(0, 4)], vec![Motion::WordForward]);
+        assert_eq!(
+            paths[&CursorPosition::new(0, 9)],
+            vec![Motion::WordForward, Motion::WordForward, Motion::Right]
+        );
+    }
+
+    #[test]
+    fn test_seed_is_reproducible() {
+        let motions = vec![Motion::WordForward, Motion::WordEnd, Motion::Down];
+        let mut a = DrillGenerator::new(motions.clone(), corpus(), Difficulty::Medium, 7);
+        let mut b = DrillGenerator::new(motions, corpus(), Difficulty::Medium, 7);
+
+        for _ in 0..5 {
+            let (ta, tb) = (a.next_task().unwrap(), b.next_task().unwrap());
+            assert_eq!(ta.description, tb.description);
+            assert_eq!(ta.target_position, tb.target_position);
+        }
+    }
+
+    #[test]
+    fn test_generated_tasks_are_solvable() {
+        let motions = vec![Motion::WordForward, Motion::WordBackward, Motion::LineEnd];
+        let mut generator = DrillGenerator::new(motions.clone(), corpus(), Difficulty::Hard, 3);
+
+        for _ in 0..10 {
+            let task = generator.next_task().unwrap();
+            let (min_len, max_len) = Difficulty::Hard.solution_length();
+            assert!((min_len..=max_len).contains(&task.expected_commands.len()));
+
+            let mut buffer = task.initial_buffer.clone();
+            for command in &task.expected_commands {
+                assert!(motions.contains(&command.as_motion().unwrap()));
+                Executor::execute(&mut buffer, *command).unwrap();
+            }
+            assert_eq!(buffer.cursor(), task.target_position);
+
+            // Hints point the way without giving the answer away.
+            let keys: String = task
+                .expected_commands
+                .iter()
+                .map(|c| c.as_motion().unwrap().to_key_str())
+                .collect();
+            assert!(task.hints.iter().all(|hint| !hint.contains(&keys)), "{:?}", task.hints);
+        }
+    }
+
+    #[test]
+    fn test_target_kinds() {
+        let buffer = Buffer::new("fn main() {");
+        let at = |column| CursorPosition::new(0, column);
+
+        assert!(TargetKind::Identifier.matches(&buffer, at(3)));
+        assert!(!TargetKind::Identifier.matches(&buffer, at(4)));
+        assert!(TargetKind::Bracket.matches(&buffer, at(7)));
+        assert!(TargetKind::LineEnd.matches(&buffer, at(10)));
+        assert!(!TargetKind::LineEnd.matches(&buffer, at(9)));
+    }
+
+    #[test]
+    fn test_targets_restrict_generated_tasks() {
+        let motions = vec![Motion::WordForward, Motion::WordEnd, Motion::LineEnd, Motion::Down];
+        let mut generator = DrillGenerator::new(motions, corpus(), Difficulty::Easy, 11)
+            .with_targets(vec![TargetKind::Bracket]);
+
+        for _ in 0..5 {
+            let task = generator.next_task().unwrap();
+            assert!(TargetKind::Bracket.matches(&task.initial_buffer, task.target_position));
+            // Only the Rust buffer has brackets.
+            assert_eq!(task.language, Language::Rust);
+        }
+    }
+
+    #[test]
+    fn test_describe_target() {
+        let buffer = Buffer::new("let value = 10;");
+        assert_eq!(
+            describe_target(&buffer, CursorPosition::new(0, 5)),
+            "Move to the 'a' in 'value' on line 1"
+        );
+        assert_eq!(
+            describe_target(&buffer, CursorPosition::new(0, 10)),
+            "Move to the '=' on line 1, column 11"
+        );
+    }
+}
@@ -1,14 +1,64 @@
-use crate::vim::{Buffer, Command, CursorPosition};
+use crate::vim::{Buffer, Command, CursorPosition, Executor, Motion};
 
 #[derive(Clone)]
 pub struct Lesson {
     pub id: usize,
     pub title: String,
+    pub section: String,
+    pub language: Language,
     pub explanation: Vec<String>,
     pub commands: Vec<Command>,
     pub tasks: Vec<Task>,
 }
 
+pub const DEFAULT_SECTION: &str = "Lessons";
+
+/// The language of a buffer's text, used to pick a syntax highlighter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    PlainText,
+    Rust,
+    JavaScript,
+    Python,
+}
+
+impl Language {
+    pub fn from_extension(extension: &str) -> Self {
+        match extension.to_ascii_lowercase().as_str() {
+            "rs" => Language::Rust,
+            "js" | "mjs" | "cjs" | "jsx" | "ts" | "tsx" => Language::JavaScript,
+            "py" | "pyw" => Language::Python,
+            _ => Language::PlainText,
+        }
+    }
+
+    pub fn from_path(path: &std::path::Path) -> Self {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map_or(Language::PlainText, Self::from_extension)
+    }
+}
+
+/// How a task's solution is judged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskCheck {
+    /// The learner must type exactly the expected commands.
+    ExactCommands,
+    /// Any sequence of allowed motions that reaches the target in no more
+    /// commands than the expected solution is accepted.
+    ShortestPath { allowed: Vec<Motion> },
+    /// Any sequence of allowed motions that reaches the target within
+    /// `max_commands` commands is accepted.
+    ReachTarget {
+        allowed: Vec<Motion>,
+        max_commands: usize,
+    },
+    /// A "where is this?" quiz: the learner is shown the expected commands
+    /// and places a guess marker where the cursor will end up.
+    PredictPosition,
+}
+
 #[derive(Clone)]
 pub struct Task {
     pub description: String,
@@ -16,6 +66,10 @@ pub struct Task {
     pub target_position: CursorPosition,
     pub expected_commands: Vec<Command>,
     pub hints: Vec<String>,
+    pub check: TaskCheck,
+    /// The language of `initial_buffer`, kept with the task so that it is
+    /// highlighted the same way outside its lesson.
+    pub language: Language,
 }
 
 impl Lesson {
@@ -29,11 +83,28 @@ impl Lesson {
         Self {
             id,
             title: title.into(),
+            section: DEFAULT_SECTION.to_string(),
+            language: Language::PlainText,
             explanation,
             commands,
             tasks,
         }
     }
+
+    /// Places the lesson in a named section of the menu.
+    pub fn in_section(mut self, section: impl Into<String>) -> Self {
+        self.section = section.into();
+        self
+    }
+
+    /// Sets the language the lesson's buffers are written in.
+    pub fn in_language(mut self, language: Language) -> Self {
+        self.language = language;
+        for task in &mut self.tasks {
+            task.language = language;
+        }
+        self
+    }
 }
 
 impl Task {
@@ -50,6 +121,52 @@ impl Task {
             target_position,
             expected_commands,
             hints,
+            check: TaskCheck::ExactCommands,
+            language: Language::PlainText,
+        }
+    }
+
+    /// A "where is this?" quiz. The target is wherever `commands` leave the
+    /// cursor when run on `initial_buffer`.
+    pub fn prediction(
+        description: impl Into<String>,
+        initial_buffer: Buffer,
+        commands: Vec<Command>,
+        hints: Vec<String>,
+    ) -> Self {
+        let start = initial_buffer.cursor();
+        let mut task = Self::new(description, initial_buffer, start, commands, hints)
+            .with_check(TaskCheck::PredictPosition);
+        task.target_position = task.solution_path().last().copied().unwrap_or(start);
+        task
+    }
+
+    pub fn is_prediction(&self) -> bool {
+        self.check == TaskCheck::PredictPosition
+    }
+
+    pub fn with_check(mut self, check: TaskCheck) -> Self {
+        self.check = check;
+        self
+    }
+
+    pub fn in_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Cursor positions visited by the expected solution, starting with the
+    /// initial position.
+    pub fn solution_path(&self) -> Vec<CursorPosition> {
+        let mut buffer = self.initial_buffer.clone();
+        let mut path = vec![buffer.cursor()];
+
+        for &command in &self.expected_commands {
+            if Executor::execute(&mut buffer, command).is_ok() {
+                path.push(buffer.cursor());
+            }
         }
+
+        path
     }
 }
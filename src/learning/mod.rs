@@ -1,7 +1,21 @@
+pub mod challenge;
 pub mod curriculum;
+pub mod generator;
 pub mod lesson;
+pub mod practice;
+pub mod progress;
+pub mod reference;
+pub mod review;
+pub mod session;
+pub mod stats;
 pub mod validator;
 
+pub use challenge::{Challenge, ChallengeRun, ChallengeSummary};
 pub use curriculum::Curriculum;
-pub use lesson::{Lesson, Task};
+pub use generator::{Difficulty, DrillGenerator, TargetKind};
+pub use lesson::{Language, Lesson, Task, TaskCheck};
+pub use progress::{ProgressStore, TaskAttempt};
+pub use reference::MotionReference;
+pub use session::{Session, SessionKind, SessionTask, TaskRef};
+pub use stats::Stats;
 pub use validator::{ValidationResult, Validator};
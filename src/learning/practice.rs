@@ -0,0 +1,47 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::vim::Buffer;
+
+/// Tabs are expanded so every character occupies a single terminal cell.
+pub const TAB_WIDTH: usize = 4;
+
+/// Loads a source file into a buffer for practice.
+pub fn load_file(path: &Path) -> io::Result<Buffer> {
+    let contents = fs::read_to_string(path)?;
+    let lines: Vec<String> = contents
+        .lines()
+        .map(|line| line.replace('\t', &" ".repeat(TAB_WIDTH)))
+        .collect();
+
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} has nothing to practice on", path.display()),
+        ));
+    }
+
+    Ok(Buffer::from_lines(lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_file_expands_tabs() {
+        let dir = std::env::temp_dir().join(format!("vex-practice-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sample.rs");
+        fs::write(&path, "fn main() {\r\n\tlet x = 1;\r\n}\r\n").unwrap();
+
+        let buffer = load_file(&path).unwrap();
+        assert_eq!(buffer.lines(), &["fn main() {", "    let x = 1;", "}"]);
+
+        fs::write(&path, "\n  \n").unwrap();
+        assert!(load_file(&path).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
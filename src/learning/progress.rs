@@ -0,0 +1,389 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::challenge::{ChallengeSummary, PersonalBest};
+use super::review::{self, ReviewState};
+use super::TaskRef;
+use crate::vim::Motion;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Oldest history entries are dropped beyond this many.
+pub const MAX_HISTORY: usize = 2000;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TaskStats {
+    pub attempts: u32,
+    pub completions: u32,
+    pub mistakes: u32,
+    pub hints_used: u32,
+    pub total_time_ms: u64,
+    pub best_time_ms: Option<u64>,
+    pub last_completed: Option<u64>,
+    pub review: Option<ReviewState>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LessonProgress {
+    pub completed: bool,
+    pub best_score: Option<u32>,
+    pub tasks: BTreeMap<usize, TaskStats>,
+}
+
+/// One solved task from any session, kept for the stats screen.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryEntry {
+    pub at: u64,
+    /// The curriculum lesson the task came from; `None` for generated tasks.
+    pub lesson_id: Option<usize>,
+    pub mistakes: u32,
+    pub hints_used: u32,
+    pub keystrokes: usize,
+    pub optimal_keystrokes: usize,
+    /// The motions of the task's optimal solution.
+    pub motions: Vec<Motion>,
+}
+
+/// Running totals over every solved task, which unlike the history are
+/// never trimmed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Totals {
+    pub tasks_solved: usize,
+    pub tasks_with_hints: usize,
+    pub hints_used: u32,
+    pub keystrokes: usize,
+    pub optimal_keystrokes: usize,
+}
+
+impl Totals {
+    pub fn from_history(history: &[HistoryEntry]) -> Self {
+        let mut totals = Self::default();
+        for entry in history {
+            totals.add(entry);
+        }
+        totals
+    }
+
+    pub fn add(&mut self, entry: &HistoryEntry) {
+        self.tasks_solved += 1;
+        if entry.hints_used > 0 {
+            self.tasks_with_hints += 1;
+        }
+        self.hints_used += entry.hints_used;
+        self.keystrokes += entry.keystrokes;
+        self.optimal_keystrokes += entry.optimal_keystrokes;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Progress {
+    pub lessons: BTreeMap<usize, LessonProgress>,
+    pub challenge_bests: BTreeMap<String, PersonalBest>,
+    /// The most recent solved tasks, at most `MAX_HISTORY` of them.
+    pub history: Vec<HistoryEntry>,
+    pub totals: Totals,
+}
+
+/// The outcome of a single solved task, as recorded by the app.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskAttempt {
+    pub attempts: u32,
+    pub mistakes: u32,
+    pub hints_used: u32,
+    pub elapsed: Duration,
+}
+
+impl TaskAttempt {
+    /// Scores the attempt out of 100. Retries and hints each cost points.
+    pub fn score(&self) -> u32 {
+        let penalty = self.attempts.saturating_sub(1) * 10 + self.mistakes * 10 + self.hints_used * 15;
+        100u32.saturating_sub(penalty).max(10)
+    }
+}
+
+/// Progress for one named profile, persisted as JSON in the XDG data directory.
+pub struct ProgressStore {
+    profile: String,
+    path: Option<PathBuf>,
+    data: Progress,
+}
+
+impl ProgressStore {
+    /// A store that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self {
+            profile: DEFAULT_PROFILE.to_string(),
+            path: None,
+            data: Progress::default(),
+        }
+    }
+
+    /// A store holding `data` that is never written to disk.
+    pub fn from_data(data: Progress) -> Self {
+        Self {
+            data,
+            ..Self::in_memory()
+        }
+    }
+
+    /// Loads the given profile from the data directory, starting fresh if it does not exist yet.
+    pub fn load(profile: &str) -> io::Result<Self> {
+        validate_profile_name(profile)?;
+
+        let dir = dirs::data_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory found"))?;
+        let path = dir.join("vex").join("profiles").join(format!("{}.json", profile));
+
+        let mut store = Self::load_from(&path)?;
+        store.profile = profile.to_string();
+        Ok(store)
+    }
+
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let mut data: Progress = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Progress::default(),
+            Err(e) => return Err(e),
+        };
+        // Profiles saved before the totals existed only have their history.
+        if data.totals == Totals::default() {
+            data.totals = Totals::from_history(&data.history);
+        }
+
+        Ok(Self {
+            profile: DEFAULT_PROFILE.to_string(),
+            path: Some(path.to_path_buf()),
+            data,
+        })
+    }
+
+    /// Writes the store to a temporary file and renames it into place so a crash
+    /// mid-write never leaves a truncated profile behind.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_string_pretty(&self.data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, path)
+    }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn data(&self) -> &Progress {
+        &self.data
+    }
+
+    pub fn lesson(&self, lesson_id: usize) -> Option<&LessonProgress> {
+        self.data.lessons.get(&lesson_id)
+    }
+
+    /// Records a solved task and reschedules its next review.
+    pub fn record_task(&mut self, task: TaskRef, attempt: TaskAttempt, expected_commands: usize) {
+        let now = unix_now();
+        let elapsed_ms = attempt.elapsed.as_millis() as u64;
+        let stats = self
+            .data
+            .lessons
+            .entry(task.lesson_id)
+            .or_default()
+            .tasks
+            .entry(task.task_index)
+            .or_default();
+
+        stats.attempts += attempt.attempts;
+        stats.completions += 1;
+        stats.mistakes += attempt.mistakes;
+        stats.hints_used += attempt.hints_used;
+        stats.total_time_ms += elapsed_ms;
+        stats.best_time_ms = Some(stats.best_time_ms.map_or(elapsed_ms, |best| best.min(elapsed_ms)));
+        stats.last_completed = Some(now);
+        stats
+            .review
+            .get_or_insert_with(ReviewState::default)
+            .review(review::quality(&attempt, expected_commands), now);
+    }
+
+    /// Adds a solved task to the totals and appends it to the history,
+    /// dropping the oldest entries past `MAX_HISTORY`.
+    pub fn record_history(&mut self, entry: HistoryEntry) {
+        self.data.totals.add(&entry);
+        let history = &mut self.data.history;
+        history.push(entry);
+        if history.len() > MAX_HISTORY {
+            history.drain(..history.len() - MAX_HISTORY);
+        }
+    }
+
+    /// Stores the run as the challenge's personal best if it beats the
+    /// previous one. Returns whether it did.
+    pub fn record_challenge(&mut self, summary: &ChallengeSummary) -> bool {
+        let best = self.data.challenge_bests.get(&summary.challenge);
+        if best.is_some_and(|best| best.score >= summary.score) {
+            return false;
+        }
+
+        self.data.challenge_bests.insert(
+            summary.challenge.clone(),
+            PersonalBest {
+                score: summary.score,
+                accuracy: summary.accuracy(),
+                average_time_ms: summary.average_time.as_millis() as u64,
+                efficiency: summary.efficiency,
+                achieved: unix_now(),
+            },
+        );
+        true
+    }
+
+    pub fn challenge_best(&self, challenge: &str) -> Option<&PersonalBest> {
+        self.data.challenge_bests.get(challenge)
+    }
+
+    pub fn record_lesson_completed(&mut self, lesson_id: usize, score: u32) {
+        let lesson = self.data.lessons.entry(lesson_id).or_default();
+        lesson.completed = true;
+        lesson.best_score = Some(lesson.best_score.map_or(score, |best| best.max(score)));
+    }
+}
+
+impl Default for ProgressStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+fn validate_profile_name(profile: &str) -> io::Result<()> {
+    let valid = !profile.is_empty()
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Invalid profile name '{}': use letters, digits, '-' or '_'", profile),
+        ))
+    }
+}
+
+/// Seconds since the Unix epoch; the clock used for progress timestamps.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(attempts: u32, mistakes: u32, hints_used: u32, ms: u64) -> TaskAttempt {
+        TaskAttempt {
+            attempts,
+            mistakes,
+            hints_used,
+            elapsed: Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn test_attempt_score() {
+        assert_eq!(attempt(1, 0, 0, 0).score(), 100);
+        assert_eq!(attempt(2, 1, 1, 0).score(), 65);
+        assert_eq!(attempt(9, 9, 3, 0).score(), 10);
+    }
+
+    #[test]
+    fn test_record_task_accumulates() {
+        let mut store = ProgressStore::in_memory();
+        store.record_task(TaskRef::new(1, 0), attempt(1, 0, 0, 900), 1);
+        store.record_task(TaskRef::new(1, 0), attempt(2, 1, 1, 400), 1);
+
+        let stats = &store.lesson(1).unwrap().tasks[&0];
+        assert_eq!(stats.attempts, 3);
+        assert_eq!(stats.completions, 2);
+        assert_eq!(stats.hints_used, 1);
+        assert_eq!(stats.best_time_ms, Some(400));
+        assert!(stats.review.is_some());
+        assert!(!store.lesson(1).is_some_and(|l| l.completed));
+
+        store.record_lesson_completed(1, 80);
+        store.record_lesson_completed(1, 60);
+        assert!(store.lesson(1).is_some_and(|l| l.completed));
+        assert_eq!(store.lesson(1).unwrap().best_score, Some(80));
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut store = ProgressStore::in_memory();
+        for at in 0..MAX_HISTORY as u64 + 5 {
+            store.record_history(HistoryEntry {
+                at,
+                ..HistoryEntry::default()
+            });
+        }
+
+        let history = &store.data().history;
+        assert_eq!(history.len(), MAX_HISTORY);
+        assert_eq!(history[0].at, 5);
+        assert_eq!(store.data().totals.tasks_solved, MAX_HISTORY + 5);
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let path = std::env::temp_dir()
+            .join(format!("vex-progress-{}", std::process::id()))
+            .join("test.json");
+
+        let mut store = ProgressStore::load_from(&path).unwrap();
+        store.record_task(TaskRef::new(2, 1), attempt(1, 0, 0, 1200), 1);
+        store.record_lesson_completed(2, 100);
+        store.save().unwrap();
+
+        let reloaded = ProgressStore::load_from(&path).unwrap();
+        assert_eq!(reloaded.data(), store.data());
+
+        // A profile from before the totals gets them from its history.
+        let entry = HistoryEntry {
+            hints_used: 2,
+            keystrokes: 3,
+            ..HistoryEntry::default()
+        };
+        fs::write(&path, r#"{"history": [{"hints_used": 2, "keystrokes": 3}]}"#).unwrap();
+        let reloaded = ProgressStore::load_from(&path).unwrap();
+        assert_eq!(reloaded.data().totals, Totals::from_history(&[entry]));
+        assert_eq!(reloaded.data().totals.tasks_with_hints, 1);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_bad_profile_names() {
+        assert!(validate_profile_name("work-laptop_2").is_ok());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("").is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use crate::vim::{Buffer, Command, CursorPosition, Executor, Motion, Options};
+
+/// A help entry for one motion, with a small example buffer that shows it
+/// in action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionReference {
+    pub motion: Motion,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub sample: &'static str,
+    pub start: CursorPosition,
+}
+
+impl MotionReference {
+    /// Reference entries for every motion, in `Motion::ALL` order.
+    pub fn all() -> Vec<MotionReference> {
+        Motion::ALL.iter().map(|&motion| Self::for_motion(motion)).collect()
+    }
+
+    pub fn for_motion(motion: Motion) -> Self {
+        let (name, description, sample, start) = match motion {
+            Motion::Left => (
+                "Left",
+                "Move one character left. Stops at the start of the line.",
+                "hello world",
+                (0, 4),
+            ),
+            Motion::Down => (
+                "Down",
+                "Move one line down, keeping the column when the line is long enough.",
+                "first line\nsecond line",
+                (0, 3),
+            ),
+            Motion::Up => (
+                "Up",
+                "Move one line up, keeping the column when the line is long enough.",
+                "first line\nsecond line",
+                (1, 3),
+            ),
+            Motion::Right => (
+                "Right",
+                "Move one character right. Stops at the last character of the line.",
+                "hello world",
+                (0, 4),
+            ),
+            Motion::WordForward => (
+                "Word forward",
+                "Move to the start of the next word. A run of punctuation counts as a word.",
+                "let value = compute(x);",
+                (0, 4),
+            ),
+            Motion::WordBackward => (
+                "Word backward",
+                "Move to the start of the current or previous word.",
+                "let value = compute(x);",
+                (0, 14),
+            ),
+            Motion::WordEnd => (
+                "Word end",
+                "Move to the end of the current or next word.",
+                "let value = compute(x);",
+                (0, 4),
+            ),
+            Motion::LineStart => (
+                "Line start",
+                "Move to the first column of the line, even if it is whitespace.",
+                "    return total;",
+                (0, 11),
+            ),
+            Motion::LineEnd => (
+                "Line end",
+                "Move to the last character of the line.",
+                "    return total;",
+                (0, 4),
+            ),
+            Motion::FileStart => (
+                "File start",
+                "Move to the first non-blank character of the first line.",
+                "fn main() {\n    run();\n}",
+                (2, 0),
+            ),
+            Motion::FileEnd => (
+                "File end",
+                "Move to the first non-blank character of the last line.",
+                "fn main() {\n    run();\n}",
+                (0, 3),
+            ),
+        };
+
+        Self {
+            motion,
+            name,
+            description,
+            sample,
+            start: CursorPosition::new(start.0, start.1),
+        }
+    }
+
+    /// The sample buffer before the motion runs, following `options`.
+    pub fn before(&self, options: &Options) -> Buffer {
+        let mut buffer = Buffer::new(self.sample);
+        buffer.set_options(options.clone());
+        buffer.set_cursor(self.start);
+        buffer
+    }
+
+    /// The sample buffer after running the motion once with `Executor`.
+    pub fn after(&self, options: &Options) -> Buffer {
+        let mut buffer = self.before(options);
+        // Motions never fail on a well-formed sample; keep the start if one does.
+        let _ = Executor::execute(&mut buffer, Command::Motion(self.motion));
+        buffer
+    }
+
+    /// Whether this entry matches a search. A query that is exactly a
+    /// motion's keys (`w`, `gg`) only finds that motion; anything else is a
+    /// case-insensitive search of the name and description.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() {
+            return true;
+        }
+        if Motion::ALL.iter().any(|m| m.to_key_str() == query) {
+            return self.motion.to_key_str() == query;
+        }
+
+        let query = query.to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self.description.to_lowercase().contains(&query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_example_moves_the_cursor() {
+        let references = MotionReference::all();
+        assert_eq!(references.len(), Motion::ALL.len());
+
+        let options = Options::default();
+        for reference in references {
+            assert_ne!(
+                reference.before(&options).cursor(),
+                reference.after(&options).cursor(),
+                "{:?}",
+                reference.motion
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let word_end = MotionReference::for_motion(Motion::WordEnd);
+        assert!(word_end.matches("e"));
+        assert!(word_end.matches("WORD"));
+        assert!(word_end.matches(""));
+        assert!(!word_end.matches("w"));
+        assert!(!word_end.matches("file"));
+    }
+
+    #[test]
+    fn test_examples_follow_options() {
+        let mut options = Options::default();
+        options.set_all(["isk+=(", "nosol"]).unwrap();
+
+        let word_end = MotionReference::for_motion(Motion::WordEnd);
+        assert_eq!(word_end.before(&options).options(), &options);
+        assert_eq!(word_end.after(&options).options(), &options);
+    }
+}
@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+
+use super::progress::{Progress, TaskAttempt};
+use super::TaskRef;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+const MIN_EASINESS: f32 = 1.3;
+
+/// Number of items assembled into a single review session.
+pub const DAILY_REVIEW_LIMIT: usize = 20;
+
+/// SM-2 scheduling state for one task.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReviewState {
+    pub easiness: f32,
+    pub interval_days: u32,
+    pub repetitions: u32,
+    pub due: u64,
+}
+
+impl Default for ReviewState {
+    fn default() -> Self {
+        Self {
+            easiness: 2.5,
+            interval_days: 0,
+            repetitions: 0,
+            due: 0,
+        }
+    }
+}
+
+impl ReviewState {
+    /// Applies an SM-2 review with the given quality (0-5) answered at `now`.
+    pub fn review(&mut self, quality: u8, now: u64) {
+        let quality = quality.min(5);
+
+        if quality < 3 {
+            self.repetitions = 0;
+            self.interval_days = 1;
+        } else {
+            self.repetitions += 1;
+            self.interval_days = match self.repetitions {
+                1 => 1,
+                2 => 6,
+                _ => (self.interval_days as f32 * self.easiness).round() as u32,
+            };
+        }
+
+        let miss = (5 - quality) as f32;
+        self.easiness = (self.easiness + 0.1 - miss * (0.08 + miss * 0.02)).max(MIN_EASINESS);
+        self.due = now + self.interval_days as u64 * SECONDS_PER_DAY;
+    }
+
+    pub fn is_due(&self, now: u64) -> bool {
+        self.due <= now
+    }
+}
+
+/// Grades a solved task on the SM-2 0-5 scale from correctness, hint usage
+/// and how long it took relative to the number of commands it needs.
+pub fn quality(attempt: &TaskAttempt, expected_commands: usize) -> u8 {
+    let mut quality: i32 = 5;
+
+    quality -= attempt.mistakes.min(2) as i32;
+    quality -= attempt.attempts.saturating_sub(1).min(1) as i32;
+    quality -= attempt.hints_used.min(3) as i32;
+
+    let allowed_ms = 2000 + 1500 * expected_commands as u128;
+    if attempt.elapsed.as_millis() > allowed_ms {
+        quality -= 1;
+    }
+
+    quality.max(0) as u8
+}
+
+/// Collects the tasks whose review is due, most overdue first.
+pub fn due_items(progress: &Progress, now: u64, limit: usize) -> Vec<TaskRef> {
+    let mut due: Vec<(u64, TaskRef)> = progress
+        .lessons
+        .iter()
+        .flat_map(|(&lesson_id, lesson)| {
+            lesson.tasks.iter().filter_map(move |(&task_index, stats)| {
+                stats
+                    .review
+                    .as_ref()
+                    .filter(|review| review.is_due(now))
+                    .map(|review| (review.due, TaskRef::new(lesson_id, task_index)))
+            })
+        })
+        .collect();
+
+    due.sort();
+    due.into_iter().take(limit).map(|(_, item)| item).collect()
+}
+
+/// The earliest upcoming review time, if anything has been learned yet.
+pub fn next_due(progress: &Progress) -> Option<u64> {
+    progress
+        .lessons
+        .values()
+        .flat_map(|lesson| lesson.tasks.values())
+        .filter_map(|stats| stats.review.as_ref().map(|review| review.due))
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learning::progress::TaskStats;
+    use std::time::Duration;
+
+    fn attempt(attempts: u32, mistakes: u32, hints_used: u32, ms: u64) -> TaskAttempt {
+        TaskAttempt {
+            attempts,
+            mistakes,
+            hints_used,
+            elapsed: Duration::from_millis(ms),
+        }
+    }
+
+    #[test]
+    fn test_intervals_grow_with_good_answers() {
+        let mut state = ReviewState::default();
+
+        state.review(5, 0);
+        assert_eq!(state.interval_days, 1);
+        state.review(5, 0);
+        assert_eq!(state.interval_days, 6);
+        state.review(5, 0);
+        assert!(state.interval_days > 6);
+        assert_eq!(state.due, state.interval_days as u64 * SECONDS_PER_DAY);
+    }
+
+    #[test]
+    fn test_failed_answer_resets_repetitions() {
+        let mut state = ReviewState::default();
+        state.review(5, 0);
+        state.review(5, 0);
+
+        state.review(1, 100);
+        assert_eq!(state.repetitions, 0);
+        assert_eq!(state.interval_days, 1);
+        assert!(state.easiness >= MIN_EASINESS);
+    }
+
+    #[test]
+    fn test_quality_penalizes_hints_mistakes_and_time() {
+        assert_eq!(quality(&attempt(1, 0, 0, 1000), 1), 5);
+        assert_eq!(quality(&attempt(1, 1, 1, 1000), 1), 3);
+        assert_eq!(quality(&attempt(1, 0, 0, 60_000), 1), 4);
+        assert_eq!(quality(&attempt(3, 5, 3, 60_000), 1), 0);
+    }
+
+    #[test]
+    fn test_due_items_sorted_by_due_date() {
+        let mut progress = Progress::default();
+        let mut add = |lesson_id: usize, task_index: usize, due: u64| {
+            progress.lessons.entry(lesson_id).or_default().tasks.insert(
+                task_index,
+                TaskStats {
+                    review: Some(ReviewState {
+                        due,
+                        ..ReviewState::default()
+                    }),
+                    ..TaskStats::default()
+                },
+            );
+        };
+        add(1, 0, 50);
+        add(2, 3, 10);
+        add(3, 1, 500);
+
+        assert_eq!(
+            due_items(&progress, 100, 10),
+            vec![TaskRef::new(2, 3), TaskRef::new(1, 0)]
+        );
+        assert_eq!(due_items(&progress, 100, 1), vec![TaskRef::new(2, 3)]);
+        assert_eq!(next_due(&progress), Some(10));
+    }
+}
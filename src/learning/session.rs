@@ -0,0 +1,116 @@
+use super::{Challenge, Curriculum, Lesson, Task};
+
+/// Identifies a task within the curriculum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TaskRef {
+    pub lesson_id: usize,
+    pub task_index: usize,
+}
+
+impl TaskRef {
+    pub fn new(lesson_id: usize, task_index: usize) -> Self {
+        Self {
+            lesson_id,
+            task_index,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionKind {
+    Lesson(usize),
+    Review,
+    Drill,
+    Practice,
+    Challenge(&'static str),
+}
+
+#[derive(Clone)]
+pub struct SessionTask {
+    pub task: Task,
+    pub origin: Option<TaskRef>,
+}
+
+/// An ordered run of tasks the learner works through, either a whole lesson
+/// or a set of tasks assembled from several lessons.
+#[derive(Clone)]
+pub struct Session {
+    pub kind: SessionKind,
+    pub title: String,
+    pub tasks: Vec<SessionTask>,
+}
+
+impl Session {
+    pub fn from_lesson(lesson: &Lesson) -> Self {
+        let tasks = lesson
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(index, task)| SessionTask {
+                task: task.clone(),
+                origin: Some(TaskRef::new(lesson.id, index)),
+            })
+            .collect();
+
+        Self {
+            kind: SessionKind::Lesson(lesson.id),
+            title: lesson.title.clone(),
+            tasks,
+        }
+    }
+
+    pub fn review(curriculum: &Curriculum, items: &[TaskRef]) -> Self {
+        let tasks = items
+            .iter()
+            .filter_map(|item| {
+                curriculum
+                    .get_lesson(item.lesson_id)
+                    .and_then(|lesson| lesson.tasks.get(item.task_index))
+                    .map(|task| SessionTask {
+                        task: task.clone(),
+                        origin: Some(*item),
+                    })
+            })
+            .collect();
+
+        Self {
+            kind: SessionKind::Review,
+            title: "Daily Review".to_string(),
+            tasks,
+        }
+    }
+
+    /// A generated session; more tasks are appended as the learner solves them.
+    pub fn endless(kind: SessionKind, title: impl Into<String>, first_task: Task) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            tasks: vec![SessionTask {
+                task: first_task,
+                origin: None,
+            }],
+        }
+    }
+
+    pub fn challenge(challenge: &Challenge, tasks: Vec<Task>) -> Self {
+        Self {
+            kind: SessionKind::Challenge(challenge.name),
+            title: format!("Challenge: {}", challenge.title),
+            tasks: tasks
+                .into_iter()
+                .map(|task| SessionTask { task, origin: None })
+                .collect(),
+        }
+    }
+
+    pub fn is_endless(&self) -> bool {
+        matches!(self.kind, SessionKind::Drill | SessionKind::Practice)
+    }
+
+    pub fn lesson_id(&self) -> Option<usize> {
+        match self.kind {
+            SessionKind::Lesson(id) => Some(id),
+            _ => None,
+        }
+    }
+}
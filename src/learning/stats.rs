@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use super::progress::{HistoryEntry, Progress};
+use super::Curriculum;
+use crate::vim::Motion;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// How far back the accuracy chart reaches.
+pub const ACCURACY_DAYS: usize = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LessonStats {
+    pub lesson_id: usize,
+    pub title: String,
+    pub solved: usize,
+    pub total: usize,
+    pub completed: bool,
+    pub best_score: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MotionStats {
+    pub motion: Motion,
+    /// Solved tasks whose optimal solution used the motion.
+    pub tasks: u32,
+    /// Of those, the tasks solved only after a mistake.
+    pub missed: u32,
+}
+
+impl MotionStats {
+    pub fn miss_rate(&self) -> f64 {
+        if self.tasks == 0 {
+            0.0
+        } else {
+            self.missed as f64 / self.tasks as f64
+        }
+    }
+}
+
+/// Everything the stats screen shows, derived from a profile's progress.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub lessons: Vec<LessonStats>,
+    /// Share of tasks solved without a mistake, per day, oldest first; `None`
+    /// for days without practice.
+    pub daily_accuracy: Vec<Option<f64>>,
+    /// Motions with at least one miss, most missed first.
+    pub most_missed: Vec<MotionStats>,
+    /// Optimal keystrokes over keystrokes typed, across every solved task.
+    pub efficiency: Option<f64>,
+    /// Consecutive days with practice, ending today or yesterday.
+    pub streak_days: u32,
+    pub hints_used: u32,
+    pub tasks_with_hints: usize,
+    pub tasks_solved: usize,
+}
+
+impl Stats {
+    pub fn compute(progress: &Progress, curriculum: &Curriculum, now: u64) -> Self {
+        let history = &progress.history;
+
+        let lessons = curriculum
+            .lessons()
+            .iter()
+            .map(|lesson| {
+                let lesson_progress = progress.lessons.get(&lesson.id);
+                let solved = lesson_progress.map_or(0, |p| {
+                    p.tasks
+                        .iter()
+                        .filter(|(index, stats)| **index < lesson.tasks.len() && stats.completions > 0)
+                        .count()
+                });
+
+                LessonStats {
+                    lesson_id: lesson.id,
+                    title: lesson.title.clone(),
+                    solved,
+                    total: lesson.tasks.len(),
+                    completed: lesson_progress.is_some_and(|p| p.completed),
+                    best_score: lesson_progress.and_then(|p| p.best_score),
+                }
+            })
+            .collect();
+
+        let totals = &progress.totals;
+        let (keystrokes, optimal) = (totals.keystrokes, totals.optimal_keystrokes);
+
+        Self {
+            lessons,
+            daily_accuracy: daily_accuracy(history, now),
+            most_missed: most_missed(history),
+            efficiency: (keystrokes > 0).then(|| (optimal as f64 / keystrokes as f64).min(1.0)),
+            streak_days: streak_days(history, now),
+            hints_used: totals.hints_used,
+            tasks_with_hints: totals.tasks_with_hints,
+            tasks_solved: totals.tasks_solved,
+        }
+    }
+}
+
+fn day(timestamp: u64) -> u64 {
+    timestamp / SECONDS_PER_DAY
+}
+
+fn daily_accuracy(history: &[HistoryEntry], now: u64) -> Vec<Option<f64>> {
+    let today = day(now);
+    let first = today.saturating_sub(ACCURACY_DAYS as u64 - 1);
+    let mut days: BTreeMap<u64, (u32, u32)> = BTreeMap::new();
+
+    for entry in history.iter().filter(|e| (first..=today).contains(&day(e.at))) {
+        let (clean, total) = days.entry(day(entry.at)).or_default();
+        *total += 1;
+        if entry.mistakes == 0 {
+            *clean += 1;
+        }
+    }
+
+    (first..=today)
+        .map(|d| days.get(&d).map(|&(clean, total)| clean as f64 / total as f64))
+        .collect()
+}
+
+fn most_missed(history: &[HistoryEntry]) -> Vec<MotionStats> {
+    let mut per_motion: Vec<MotionStats> = Motion::ALL
+        .iter()
+        .map(|&motion| MotionStats {
+            motion,
+            tasks: 0,
+            missed: 0,
+        })
+        .collect();
+
+    for entry in history {
+        for stats in per_motion.iter_mut().filter(|s| entry.motions.contains(&s.motion)) {
+            stats.tasks += 1;
+            if entry.mistakes > 0 {
+                stats.missed += 1;
+            }
+        }
+    }
+
+    per_motion.retain(|s| s.missed > 0);
+    per_motion.sort_by(|a, b| {
+        b.missed
+            .cmp(&a.missed)
+            .then(b.miss_rate().total_cmp(&a.miss_rate()))
+    });
+    per_motion
+}
+
+fn streak_days(history: &[HistoryEntry], now: u64) -> u32 {
+    let mut days: Vec<u64> = history.iter().map(|e| day(e.at)).collect();
+    days.sort_unstable();
+    days.dedup();
+
+    // A streak stays alive until a whole day passes without practice.
+    let Some(&last) = days.last() else {
+        return 0;
+    };
+    if last + 1 < day(now) {
+        return 0;
+    }
+
+    let mut streak = 1;
+    for pair in days.windows(2).rev() {
+        if pair[0] + 1 != pair[1] {
+            break;
+        }
+        streak += 1;
+    }
+    streak
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learning::progress::{LessonProgress, Totals};
+
+    const NOW: u64 = 100 * SECONDS_PER_DAY + 3600;
+
+    fn entry(days_ago: u64, mistakes: u32, motions: &[Motion]) -> HistoryEntry {
+        HistoryEntry {
+            at: NOW - days_ago * SECONDS_PER_DAY,
+            lesson_id: None,
+            mistakes,
+            hints_used: 0,
+            keystrokes: 4,
+            optimal_keystrokes: 2,
+            motions: motions.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_streak_days() {
+        let history = vec![entry(5, 0, &[]), entry(2, 0, &[]), entry(1, 0, &[]), entry(1, 0, &[])];
+        assert_eq!(streak_days(&history, NOW), 2);
+        assert_eq!(streak_days(&history, NOW + SECONDS_PER_DAY), 0);
+        assert_eq!(streak_days(&[], NOW), 0);
+    }
+
+    #[test]
+    fn test_daily_accuracy() {
+        let history = vec![entry(0, 0, &[]), entry(0, 2, &[]), entry(3, 0, &[]), entry(90, 1, &[])];
+        let accuracy = daily_accuracy(&history, NOW);
+
+        assert_eq!(accuracy.len(), ACCURACY_DAYS);
+        assert_eq!(accuracy[ACCURACY_DAYS - 1], Some(0.5));
+        assert_eq!(accuracy[ACCURACY_DAYS - 4], Some(1.0));
+        assert_eq!(accuracy.iter().flatten().count(), 2);
+    }
+
+    #[test]
+    fn test_compute() {
+        let mut progress = Progress {
+            history: vec![
+                entry(0, 1, &[Motion::WordEnd, Motion::LineEnd]),
+                entry(0, 2, &[Motion::WordEnd]),
+                entry(0, 0, &[Motion::LineEnd]),
+            ],
+            ..Progress::default()
+        };
+        progress.history[0].hints_used = 2;
+        progress.totals = Totals::from_history(&progress.history);
+        let mut lesson = LessonProgress::default();
+        lesson.tasks.entry(0).or_default().completions = 1;
+        progress.lessons.insert(1, lesson);
+
+        let stats = Stats::compute(&progress, &Curriculum::new(), NOW);
+
+        assert_eq!(stats.lessons[0].solved, 1);
+        assert!(!stats.lessons[0].completed);
+        assert_eq!(stats.most_missed[0].motion, Motion::WordEnd);
+        assert_eq!(stats.most_missed[0].missed, 2);
+        assert_eq!(stats.most_missed[1].motion, Motion::LineEnd);
+        assert_eq!(stats.most_missed.len(), 2);
+        assert_eq!(stats.efficiency, Some(0.5));
+        assert_eq!(stats.hints_used, 2);
+        assert_eq!(stats.tasks_with_hints, 1);
+        assert_eq!(stats.tasks_solved, 3);
+        assert_eq!(stats.streak_days, 1);
+    }
+}
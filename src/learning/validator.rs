@@ -1,17 +1,25 @@
-use crate::vim::{Command, CursorPosition};
+use super::{Task, TaskCheck};
+use crate::vim::{Command, CursorPosition, Motion};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ValidationResult {
     Correct,
+    /// The commands so far are on track but the task isn't finished yet.
+    InProgress { feedback: String },
     Incorrect { feedback: String },
     WrongPosition { feedback: String },
 }
 
-pub struct Validator;
+pub struct Validator {
+    /// The key feedback names for trying a task again.
+    pub reset_key: String,
+}
 
 impl Validator {
     pub fn new() -> Self {
-        Self
+        Self {
+            reset_key: "r".to_string(),
+        }
     }
 
     pub fn validate(
@@ -21,6 +29,12 @@ impl Validator {
         final_position: CursorPosition,
         target_position: CursorPosition,
     ) -> ValidationResult {
+        if actual.len() < expected.len() && expected.starts_with(actual) {
+            return ValidationResult::InProgress {
+                feedback: format!("Keep going ({}/{}).", actual.len(), expected.len()),
+            };
+        }
+
         if actual.len() != expected.len() {
             return ValidationResult::Incorrect {
                 feedback: format!(
@@ -48,15 +62,159 @@ impl Validator {
         if final_position != target_position {
             return ValidationResult::WrongPosition {
                 feedback: format!(
-                    "Commands were correct, but cursor is at position ({}, {}). Target is ({}, {}).",
-                    final_position.line, final_position.column,
-                    target_position.line, target_position.column
+                    "Commands were correct, but the cursor ended at line {}, column {} instead of line {}, column {}.",
+                    final_position.line + 1,
+                    final_position.column + 1,
+                    target_position.line + 1,
+                    target_position.column + 1
                 ),
             };
         }
 
         ValidationResult::Correct
     }
+
+    /// Validates the commands executed so far against a task, according to its check.
+    pub fn validate_task(
+        &self,
+        task: &Task,
+        actual: &[Command],
+        final_position: CursorPosition,
+    ) -> ValidationResult {
+        match &task.check {
+            TaskCheck::ExactCommands => self.validate(
+                &task.expected_commands,
+                actual,
+                final_position,
+                task.target_position,
+            ),
+            TaskCheck::ShortestPath { allowed } => self.validate_shortest_path(
+                allowed,
+                task.expected_commands.len(),
+                actual,
+                final_position,
+                task.target_position,
+            ),
+            TaskCheck::ReachTarget {
+                allowed,
+                max_commands,
+            } => self.validate_reach_target(
+                allowed,
+                *max_commands,
+                actual,
+                final_position,
+                task.target_position,
+            ),
+            TaskCheck::PredictPosition => ValidationResult::Incorrect {
+                feedback: "Don't type the keys: mark the cell where they would leave the cursor."
+                    .to_string(),
+            },
+        }
+    }
+
+    /// Judges a guess at where a "where is this?" quiz leaves the cursor.
+    pub fn validate_guess(&self, task: &Task, guess: CursorPosition) -> ValidationResult {
+        if guess == task.target_position {
+            return ValidationResult::Correct;
+        }
+
+        let hint = if guess.line != task.target_position.line {
+            "Check which line the keys end on."
+        } else if guess.column < task.target_position.column {
+            "The cursor ends further right."
+        } else {
+            "The cursor ends further left."
+        };
+        ValidationResult::Incorrect {
+            feedback: format!("Not there. {}", hint),
+        }
+    }
+
+    pub fn validate_reach_target(
+        &self,
+        allowed: &[Motion],
+        max_commands: usize,
+        actual: &[Command],
+        final_position: CursorPosition,
+        target_position: CursorPosition,
+    ) -> ValidationResult {
+        if let Some(result) = Self::check_allowed(allowed, actual) {
+            return result;
+        }
+
+        if final_position == target_position {
+            ValidationResult::Correct
+        } else if actual.len() >= max_commands {
+            ValidationResult::Incorrect {
+                feedback: format!("Out of moves: the target can't take more than {} commands.", max_commands),
+            }
+        } else {
+            ValidationResult::InProgress {
+                feedback: format!("{} of {} commands used.", actual.len(), max_commands),
+            }
+        }
+    }
+
+    fn check_allowed(allowed: &[Motion], actual: &[Command]) -> Option<ValidationResult> {
+        let motion = actual
+            .iter()
+            .filter_map(|c| c.as_motion())
+            .find(|m| !allowed.contains(m))?;
+        let keys: Vec<&str> = allowed.iter().map(|m| m.to_key_str()).collect();
+
+        Some(ValidationResult::Incorrect {
+            feedback: format!(
+                "'{}' isn't part of this drill. Use only: {}.",
+                motion.to_key_str(),
+                keys.join(", ")
+            ),
+        })
+    }
+
+    pub fn validate_shortest_path(
+        &self,
+        allowed: &[Motion],
+        optimal_len: usize,
+        actual: &[Command],
+        final_position: CursorPosition,
+        target_position: CursorPosition,
+    ) -> ValidationResult {
+        if let Some(result) = Self::check_allowed(allowed, actual) {
+            return result;
+        }
+
+        if final_position == target_position {
+            if actual.len() <= optimal_len {
+                return ValidationResult::Correct;
+            }
+
+            return ValidationResult::Incorrect {
+                feedback: format!(
+                    "Reached the target in {} commands, but it can be done in {}. Press '{}' to try again.",
+                    actual.len(),
+                    optimal_len,
+                    self.reset_key
+                ),
+            };
+        }
+
+        if actual.len() < optimal_len {
+            ValidationResult::InProgress {
+                feedback: format!("{} of {} commands used. Keep going!", actual.len(), optimal_len),
+            }
+        } else {
+            ValidationResult::WrongPosition {
+                feedback: format!(
+                    "Used {} commands, but the cursor ended at line {}, column {} instead of line {}, column {}.",
+                    actual.len(),
+                    final_position.line + 1,
+                    final_position.column + 1,
+                    target_position.line + 1,
+                    target_position.column + 1
+                ),
+            }
+        }
+    }
 }
 
 impl Default for Validator {
@@ -104,4 +262,104 @@ mod tests {
             _ => panic!("Expected incorrect result"),
         }
     }
+
+    #[test]
+    fn test_correct_prefix_is_in_progress() {
+        let validator = Validator::new();
+        let expected = vec![Command::Motion(Motion::Right), Command::Motion(Motion::Right)];
+
+        let result = validator.validate(
+            &expected,
+            &expected[..1],
+            CursorPosition::new(0, 1),
+            CursorPosition::new(0, 2),
+        );
+        assert_eq!(
+            result,
+            ValidationResult::InProgress {
+                feedback: "Keep going (1/2).".to_string()
+            }
+        );
+
+        let result = validator.validate(
+            &expected,
+            &[Command::Motion(Motion::Left)],
+            CursorPosition::new(0, 0),
+            CursorPosition::new(0, 2),
+        );
+        assert!(matches!(result, ValidationResult::Incorrect { .. }));
+    }
+
+    #[test]
+    fn test_shortest_path_accepts_any_optimal_route() {
+        let validator = Validator::new();
+        let allowed = [Motion::WordForward, Motion::WordEnd, Motion::Right];
+        let target = CursorPosition::new(0, 4);
+
+        let result = validator.validate_shortest_path(
+            &allowed,
+            2,
+            &[Command::Motion(Motion::WordEnd), Command::Motion(Motion::Right)],
+            target,
+            target,
+        );
+        assert_eq!(result, ValidationResult::Correct);
+
+        let result = validator.validate_shortest_path(
+            &allowed,
+            1,
+            &[Command::Motion(Motion::WordEnd), Command::Motion(Motion::Right)],
+            target,
+            target,
+        );
+        assert!(matches!(result, ValidationResult::Incorrect { .. }));
+
+        let result = validator.validate_shortest_path(
+            &allowed,
+            2,
+            &[Command::Motion(Motion::Left)],
+            CursorPosition::new(0, 0),
+            target,
+        );
+        assert!(matches!(result, ValidationResult::Incorrect { .. }));
+    }
+
+    #[test]
+    fn test_reach_target_allows_longer_routes() {
+        let validator = Validator::new();
+        let allowed = [Motion::Right, Motion::Left];
+        let target = CursorPosition::new(0, 1);
+        let wander = [
+            Command::Motion(Motion::Right),
+            Command::Motion(Motion::Right),
+            Command::Motion(Motion::Left),
+        ];
+
+        let result = validator.validate_reach_target(&allowed, 3, &wander, target, target);
+        assert_eq!(result, ValidationResult::Correct);
+
+        let result = validator.validate_reach_target(&allowed, 3, &wander[..2], CursorPosition::new(0, 2), target);
+        assert!(matches!(result, ValidationResult::InProgress { .. }));
+
+        let result = validator.validate_reach_target(&allowed, 2, &wander[..2], CursorPosition::new(0, 2), target);
+        assert!(matches!(result, ValidationResult::Incorrect { .. }));
+    }
+
+    #[test]
+    fn test_validate_guess() {
+        let validator = Validator::new();
+        let task = Task::prediction(
+            "Where does 'ww' land?",
+            crate::vim::Buffer::new("let x = 1;"),
+            vec![Command::Motion(Motion::WordForward), Command::Motion(Motion::WordForward)],
+            vec![],
+        );
+
+        assert_eq!(task.target_position, CursorPosition::new(0, 6));
+        assert_eq!(validator.validate_guess(&task, CursorPosition::new(0, 6)), ValidationResult::Correct);
+        assert!(matches!(
+            validator.validate_guess(&task, CursorPosition::new(0, 4)),
+            ValidationResult::Incorrect { .. }
+        ));
+    }
 }
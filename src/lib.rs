@@ -0,0 +1,59 @@
+//! VEX, a Vim movement trainer.
+//!
+//! The crate is split in layers that can be used on their own:
+//!
+//! - [`vim`]: a small Vim engine: buffers, motions, key parsing and execution.
+//! - [`learning`]: the curriculum, task validation, drills, challenges and
+//!   progress tracking.
+//! - [`App`]: the trainer's state machine. It reacts to [`Event`]s and never
+//!   touches the terminal, so lessons can be driven headlessly.
+//! - [`ui`]: the ratatui front end used by the `vex` binary.
+//!
+//! # Driving a lesson without a terminal
+//!
+//! ```
+//! use vex::{App, AppMode};
+//!
+//! let mut app = App::new();
+//! app.send_keys("1"); // start lesson 1 from the menu
+//! assert_eq!(app.mode, AppMode::Lesson);
+//!
+//! let task = app.current_task().unwrap();
+//! assert!(task.description.contains("press l"));
+//!
+//! app.send_keys("l");
+//! assert!(app.task_completed);
+//! assert_eq!(app.feedback_message, "Correct! Press Space to continue.");
+//! ```
+//!
+//! # Using the motion engine directly
+//!
+//! ```
+//! use vex::vim::{Buffer, CommandParser, CursorPosition, Executor, Key};
+//! use vex::vim::parser::ParseResult;
+//!
+//! let mut buffer = Buffer::new("let total = price * quantity;");
+//! let mut parser = CommandParser::new();
+//!
+//! for key in Key::parse_sequence("wwe") {
+//!     if let ParseResult::Complete(command) = parser.parse_keystroke(key) {
+//!         Executor::execute(&mut buffer, command).unwrap();
+//!     }
+//! }
+//! // On "total", then "=", then the end of "price".
+//! assert_eq!(buffer.cursor(), CursorPosition::new(0, 16));
+//! ```
+
+pub mod app;
+pub mod cli;
+pub mod config;
+pub mod export;
+pub mod headless;
+pub mod learning;
+pub mod recording;
+pub mod ui;
+pub mod vim;
+
+pub use app::{App, AppMode};
+pub use ui::Event;
+pub use vim::Key;
@@ -1,33 +1,127 @@
-mod app;
-mod learning;
-mod ui;
-mod vim;
-
-use app::App;
 use clap::Parser;
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
-use ui::EventHandler;
-
-#[derive(Parser)]
-#[command(name = "vex")]
-#[command(about = "VEX - Vim Movement Trainer", long_about = None)]
-struct Cli {}
+use std::path::Path;
+use std::time::{Duration, Instant};
+use vex::app::App;
+use vex::cli::{Cli, Commands};
+use vex::config::Config;
+use vex::export::{self, ExportFormat};
+use vex::headless;
+use vex::learning::{
+    progress::{unix_now, DEFAULT_PROFILE},
+    ProgressStore,
+};
+use vex::recording::{self, Player, Recorder, Recording};
+use vex::ui::{self, Event, EventHandler};
+use vex::vim::{Key, KeyCode};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let _cli = Cli::parse();
+    let cli = Cli::parse();
 
-    let mut terminal = setup_terminal()?;
+    match &cli.command {
+        Some(Commands::Run {
+            lesson,
+            keys,
+            keys_file,
+        }) => return run_headless(*lesson, keys.as_deref(), keys_file.as_deref()),
+        Some(Commands::Replay { file, speed }) => return replay(file, *speed),
+        Some(Commands::Export {
+            recording,
+            format,
+            output,
+        }) => return export(recording, *format, output.as_deref()),
+        _ => {}
+    }
 
-    let mut app = App::new();
+    let config = Config::load_or_default(cli.config.as_deref())?;
+    let profile = cli
+        .profile
+        .as_deref()
+        .or(config.profile.as_deref())
+        .unwrap_or(DEFAULT_PROFILE);
+    let progress = ProgressStore::load(profile)?;
+
+    let seed = unix_now();
+    let mut recorder = match &cli.record {
+        Some(path) => Some(
+            Recorder::create(
+                path,
+                seed,
+                &Cli::recorded_args(std::env::args().skip(1)),
+                &config,
+                progress.data(),
+            )
+            .map_err(|e| format!("Could not create {}: {}", path.display(), e))?,
+        ),
+        None => None,
+    };
+
+    let mut app = App::with_progress(progress);
+    cli.configure(&mut app, &config, seed)?;
+    if recorder.is_some() {
+        app.seed = Some(seed);
+    }
+
+    let mut terminal = setup_terminal(config.lesson.mouse && !cli.no_mouse)?;
+    let size = terminal.size()?;
     let event_handler = EventHandler::new();
 
-    let res = run_app(&mut terminal, &mut app, &event_handler);
+    let res = run_app(
+        &mut terminal,
+        &mut app,
+        &event_handler,
+        recorder.as_mut(),
+        Event::Resize(size.width, size.height),
+    );
+
+    restore_terminal(&mut terminal)?;
+
+    if let Err(err) = res {
+        eprintln!("Error: {:?}", err);
+    }
+    if let Some(recorder) = recorder {
+        recorder.finish()?;
+    }
+
+    Ok(())
+}
 
+fn run_headless(
+    lesson: usize,
+    keys: Option<&str>,
+    keys_file: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let keys = match (keys, keys_file) {
+        (Some(keys), _) => keys.to_string(),
+        (None, Some(path)) => headless::read_keys(
+            &std::fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?,
+        ),
+        (None, None) => String::new(),
+    };
+    let report = headless::run_lesson(lesson, &Key::parse_sequence(&keys))?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.completed {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn load_recording(path: &Path) -> Result<(Recording, App), String> {
+    let recording = Recording::load(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let app = recording.app()?;
+    Ok((recording, app))
+}
+
+fn replay(path: &Path, speed: f64) -> Result<(), Box<dyn std::error::Error>> {
+    let (recording, mut app) = load_recording(path)?;
+    let mut terminal = setup_terminal(false)?;
+    let res = run_replay(&mut terminal, &mut app, Player::new(recording, speed));
     restore_terminal(&mut terminal)?;
 
     if let Err(err) = res {
@@ -37,10 +131,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, Box<dyn std::error::Error>> {
+fn setup_terminal(
+    mouse: bool,
+) -> Result<Terminal<CrosstermBackend<io::Stdout>>, Box<dyn std::error::Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
+    if mouse {
+        execute!(stdout, EnableMouseCapture)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -50,23 +149,114 @@ fn restore_terminal(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+fn export(
+    path: &Path,
+    format: ExportFormat,
+    output: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (recording, mut app) = load_recording(path)?;
+    let frames = export::render_frames(&mut app, &recording);
+
+    match (format, output) {
+        (ExportFormat::Asciicast, output) => {
+            let title = std::iter::once("vex".to_string())
+                .chain(recording.args.iter().cloned())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let cast = export::asciicast(&frames, &title);
+            match output {
+                Some(output) => std::fs::write(output, cast)?,
+                None => print!("{}", cast),
+            }
+        }
+        (ExportFormat::Frames, Some(dir)) => {
+            std::fs::create_dir_all(dir)?;
+            for (index, frame) in frames.iter().enumerate() {
+                std::fs::write(dir.join(format!("frame-{:04}.txt", index + 1)), frame.text())?;
+            }
+            println!("Wrote {} frames to {}", frames.len(), dir.display());
+        }
+        (ExportFormat::Frames, None) => {
+            for (index, frame) in frames.iter().enumerate() {
+                println!("--- frame {} at {:.2}s ---", index + 1, frame.at as f64 / 1000.0);
+                print!("{}", frame.text());
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
     event_handler: &EventHandler,
+    mut recorder: Option<&mut Recorder>,
+    initial: Event,
 ) -> io::Result<()> {
+    let mut pending = Some(initial);
+
     while app.is_running() {
         terminal.draw(|f| ui::render_ui(f, app))?;
 
-        if let Some(event) = event_handler.poll_event(std::time::Duration::from_millis(100))? {
+        let event = pending.take().or(event_handler.poll_event(Duration::from_millis(100))?);
+        // A mapping that timed out before the event resolves first.
+        app.tick(Instant::now());
+        if let Some(event) = event {
+            if let Some(recorder) = recorder.as_deref_mut() {
+                recorder.record(&event)?;
+            }
             app.handle_event(event);
         }
     }
 
     Ok(())
 }
+
+fn run_replay(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    mut player: Player,
+) -> io::Result<()> {
+    let event_handler = EventHandler::new();
+    let mut last = Instant::now();
+    // The app's clock follows the recording, whatever the playback speed.
+    let start = app.clock;
+
+    loop {
+        terminal.draw(|f| {
+            ui::render_ui(f, app);
+            ui::components::render_replay_status(f, app, &player.status());
+        })?;
+
+        let mut due = Vec::new();
+        match event_handler.poll_event(Duration::from_millis(50))? {
+            Some(Event::Quit) => break,
+            Some(Event::Key(key)) => match (key.as_char(), key.code) {
+                (Some('q'), _) | (None, KeyCode::Esc) => break,
+                (Some(' '), _) => player.toggle_pause(),
+                (Some('n' | '.'), _) | (None, KeyCode::Right) => due.extend(player.step()),
+                (Some('+' | '>'), _) => player.faster(),
+                (Some('-' | '<'), _) => player.slower(),
+                _ => {}
+            },
+            _ => {}
+        }
+
+        let now = Instant::now();
+        due.extend(player.advance(now - last));
+        last = now;
+
+        for recorded in due {
+            recording::play_event(app, start, recorded);
+        }
+        app.tick(start + player.position());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,529 @@
+//! Recording the event stream of a session and playing it back.
+//!
+//! A recording is a small text file. The header names the format version,
+//! the seed used for generated tasks and the command line the session was
+//! started with. An optional `config` line holds the configuration file's
+//! settings as JSON, and an optional `progress` line the Unix time and the
+//! profile's progress when the session started, which reviews depend on.
+//! Each further line is one event, prefixed with the milliseconds since the
+//! previous one:
+//!
+//! ```text
+//! vex-recording 1 1718000000 ["drill","--motions","wb"]
+//! config {"profile":null,"theme":null,"lesson":{...},"keys":{...}}
+//! progress 1718000000 {"lessons":{...},"challenge_bests":{},...}
+//! 0 resize 80 24
+//! 850 key 1
+//! 1200 key w
+//! 40 click 12 7
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+
+use crate::app::App;
+use crate::cli::Cli;
+use crate::config::Config;
+use crate::learning::progress::{unix_now, Progress};
+use crate::learning::ProgressStore;
+use crate::ui::events::MouseAction;
+use crate::ui::Event;
+use crate::vim::Key;
+
+const MAGIC: &str = "vex-recording";
+const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// Milliseconds since the recording started.
+    pub at: u64,
+    pub event: Event,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    /// Seed for drills and challenges, so they generate the same tasks.
+    pub seed: u64,
+    /// Command line arguments after `vex`, without `--record`.
+    pub args: Vec<String>,
+    /// The configuration the session ran with.
+    pub config: Config,
+    /// The profile's progress when the session started, if it had any.
+    pub progress: Option<ProgressSnapshot>,
+    pub events: Vec<RecordedEvent>,
+}
+
+/// A profile's progress and the Unix time it was taken at. Which tasks are
+/// due for review depends on both.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressSnapshot {
+    pub at: u64,
+    pub progress: Progress,
+}
+
+impl Recording {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        Self::parse(BufReader::new(file))
+    }
+
+    pub fn parse(reader: impl BufRead) -> io::Result<Self> {
+        let mut lines = reader.lines();
+        let header = lines.next().transpose()?.unwrap_or_default();
+        let (seed, args) = parse_header(&header).ok_or_else(|| invalid("Not a VEX recording"))?;
+
+        let mut config = Config::default();
+        let mut progress = None;
+        let mut events = Vec::new();
+        let mut at = 0;
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(json) = line.strip_prefix("config ") {
+                config = serde_json::from_str(json)
+                    .map_err(|e| invalid(&format!("Line {}: bad config: {}", index + 2, e)))?;
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix("progress ") {
+                progress = Some(parse_progress(rest).map_err(|e| {
+                    invalid(&format!("Line {}: bad progress: {}", index + 2, e))
+                })?);
+                continue;
+            }
+            let (delta, event) = parse_event_line(&line)
+                .ok_or_else(|| invalid(&format!("Line {}: cannot read '{}'", index + 2, line)))?;
+            at += delta;
+            events.push(RecordedEvent { at, event });
+        }
+
+        Ok(Self {
+            seed,
+            args,
+            config,
+            progress,
+            events,
+        })
+    }
+
+    /// An app set up the way the recorded session started, with an unsaved
+    /// copy of the recorded progress.
+    pub fn app(&self) -> Result<App, String> {
+        let args = std::iter::once("vex".to_string()).chain(self.args.iter().cloned());
+        let cli = Cli::try_parse_from(args).map_err(|e| e.to_string())?;
+
+        let mut app = match &self.progress {
+            Some(snapshot) => {
+                let store = ProgressStore::from_data(snapshot.progress.clone());
+                let mut app = App::with_progress(store);
+                app.review_time = Some(snapshot.at);
+                app
+            }
+            None => App::new(),
+        };
+        cli.configure(&mut app, &self.config, self.seed)?;
+        app.seed = Some(self.seed);
+        Ok(app)
+    }
+
+    /// Length of the recording in milliseconds.
+    pub fn duration(&self) -> u64 {
+        self.events.last().map_or(0, |e| e.at)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn parse_progress(rest: &str) -> Result<ProgressSnapshot, String> {
+    let (at, json) = rest.split_once(' ').ok_or("missing time")?;
+    Ok(ProgressSnapshot {
+        at: at.parse().map_err(|_| format!("bad time '{}'", at))?,
+        progress: serde_json::from_str(json).map_err(|e| e.to_string())?,
+    })
+}
+
+fn parse_header(line: &str) -> Option<(u64, Vec<String>)> {
+    let mut parts = line.splitn(4, ' ');
+    if parts.next()? != MAGIC || parts.next()?.parse::<u32>().ok()? != VERSION {
+        return None;
+    }
+    let seed = parts.next()?.parse().ok()?;
+    let args = serde_json::from_str(parts.next().unwrap_or("[]")).ok()?;
+    Some((seed, args))
+}
+
+fn format_event(event: &Event) -> String {
+    match event {
+        Event::Key(key) => format!("key {}", key),
+        Event::Quit => "quit".to_string(),
+        Event::Resize(width, height) => format!("resize {} {}", width, height),
+        Event::Mouse { action, column, row } => {
+            let action = match action {
+                MouseAction::Click => "click",
+                MouseAction::ScrollUp => "scroll-up",
+                MouseAction::ScrollDown => "scroll-down",
+            };
+            format!("{} {} {}", action, column, row)
+        }
+    }
+}
+
+fn parse_event_line(line: &str) -> Option<(u64, Event)> {
+    let mut parts = line.splitn(3, ' ');
+    let delta = parts.next()?.parse().ok()?;
+    let kind = parts.next()?;
+    let rest = parts.next().unwrap_or("");
+
+    let pair = || -> Option<(u16, u16)> {
+        let (a, b) = rest.split_once(' ')?;
+        Some((a.parse().ok()?, b.parse().ok()?))
+    };
+    let mouse = |action| pair().map(|(column, row)| Event::Mouse { action, column, row });
+
+    let event = match kind {
+        "key" => Event::Key(rest.parse::<Key>().ok()?),
+        "quit" => Event::Quit,
+        "resize" => pair().map(|(width, height)| Event::Resize(width, height))?,
+        "click" => mouse(MouseAction::Click)?,
+        "scroll-up" => mouse(MouseAction::ScrollUp)?,
+        "scroll-down" => mouse(MouseAction::ScrollDown)?,
+        _ => return None,
+    };
+    Some((delta, event))
+}
+
+/// Writes events to a recording file as they happen. It sits between the
+/// `EventHandler` and `App::handle_event`.
+pub struct Recorder {
+    writer: BufWriter<File>,
+    last: Instant,
+}
+
+impl Recorder {
+    /// Starts a recording. `progress` is the profile's progress, stored so
+    /// that replays find the same reviews due and show the same badges.
+    pub fn create(
+        path: &Path,
+        seed: u64,
+        args: &[String],
+        config: &Config,
+        progress: &Progress,
+    ) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let args = serde_json::to_string(args).map_err(io::Error::other)?;
+        writeln!(writer, "{} {} {} {}", MAGIC, VERSION, seed, args)?;
+        if *config != Config::default() {
+            let config = serde_json::to_string(config).map_err(io::Error::other)?;
+            writeln!(writer, "config {}", config)?;
+        }
+        if *progress != Progress::default() {
+            let progress = serde_json::to_string(progress).map_err(io::Error::other)?;
+            writeln!(writer, "progress {} {}", unix_now(), progress)?;
+        }
+
+        Ok(Self {
+            writer,
+            last: Instant::now(),
+        })
+    }
+
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        let now = Instant::now();
+        let delta = now.duration_since(self.last).as_millis();
+        self.last = now;
+        writeln!(self.writer, "{} {}", delta, format_event(event))
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Hands a recorded event to `app` with the app's clock at the event's
+/// recorded time, counted from `start`. The recorded quit is skipped: it
+/// ends the recording, not the replay.
+pub fn play_event(app: &mut App, start: Instant, recorded: RecordedEvent) {
+    if recorded.event == Event::Quit {
+        return;
+    }
+    app.tick(start + Duration::from_millis(recorded.at));
+    app.handle_event(recorded.event);
+}
+
+/// Plays a recording back at an adjustable speed.
+pub struct Player {
+    recording: Recording,
+    next: usize,
+    /// How far into the recording playback is, in milliseconds.
+    position: f64,
+    pub speed: f64,
+    pub paused: bool,
+}
+
+impl Player {
+    pub const MIN_SPEED: f64 = 0.25;
+    pub const MAX_SPEED: f64 = 16.0;
+
+    pub fn new(recording: Recording, speed: f64) -> Self {
+        Self {
+            recording,
+            next: 0,
+            position: 0.0,
+            speed: speed.clamp(Self::MIN_SPEED, Self::MAX_SPEED),
+            paused: false,
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    /// How far into the recording playback is.
+    pub fn position(&self) -> Duration {
+        Duration::from_secs_f64(self.position / 1000.0)
+    }
+
+    /// Moves playback on by `elapsed` wall-clock time and returns the events
+    /// that became due, with their recorded times.
+    pub fn advance(&mut self, elapsed: Duration) -> Vec<RecordedEvent> {
+        if self.paused {
+            return Vec::new();
+        }
+        self.position += elapsed.as_secs_f64() * 1000.0 * self.speed;
+
+        let mut due = Vec::new();
+        while let Some(recorded) = self.recording.events.get(self.next) {
+            if recorded.at as f64 > self.position {
+                break;
+            }
+            due.push(recorded.clone());
+            self.next += 1;
+        }
+        due
+    }
+
+    /// Pauses and returns the next event, jumping to its time.
+    pub fn step(&mut self) -> Option<RecordedEvent> {
+        self.paused = true;
+        let recorded = self.recording.events.get(self.next)?;
+        self.position = recorded.at as f64;
+        self.next += 1;
+        Some(recorded.clone())
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed * 2.0).min(Self::MAX_SPEED);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = (self.speed / 2.0).max(Self::MIN_SPEED);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+
+    /// A one-line summary such as `▶ 2x  12.3s / 40.0s  event 18/96`.
+    pub fn status(&self) -> String {
+        let state = if self.is_finished() {
+            "■"
+        } else if self.paused {
+            "⏸"
+        } else {
+            "▶"
+        };
+        let position = (self.position as u64).min(self.recording.duration());
+        format!(
+            "{} {}x  {:.1}s / {:.1}s  event {}/{}",
+            state,
+            self.speed,
+            position as f64 / 1000.0,
+            self.recording.duration() as f64 / 1000.0,
+            self.next,
+            self.recording.events.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::learning::{SessionKind, TaskAttempt, TaskRef};
+    use crate::vim::KeyCode;
+
+    const SAMPLE: &str = "vex-recording 1 42 [\"drill\",\"--motions\",\"wb\"]\n\
+                          0 resize 80 24\n\
+                          500 key w\n\
+                          250 key <C-d>\n\
+                          250 click 3 4\n\
+                          1000 key <Esc>\n";
+
+    #[test]
+    fn test_parse_and_format() {
+        let recording = Recording::parse(SAMPLE.as_bytes()).unwrap();
+
+        assert_eq!(recording.seed, 42);
+        assert_eq!(recording.args, vec!["drill", "--motions", "wb"]);
+        assert_eq!(recording.events.len(), 5);
+        assert_eq!(recording.events[2].at, 750);
+        assert_eq!(recording.events[2].event, Event::Key(Key::ctrl('d')));
+        assert_eq!(recording.duration(), 2000);
+
+        for line in SAMPLE.lines().skip(1) {
+            let (_, event) = parse_event_line(line).unwrap();
+            assert!(line.ends_with(&format_event(&event)));
+        }
+        assert!(Recording::parse("hello\n".as_bytes()).is_err());
+        assert!(Recording::parse("vex-recording 1 0 []\n10 jump\n".as_bytes()).is_err());
+        assert_eq!(recording.config, Config::default());
+    }
+
+    #[test]
+    fn test_app_starts_like_the_recording() {
+        let recording = Recording::parse(SAMPLE.as_bytes()).unwrap();
+        let app = recording.app().unwrap();
+
+        assert_eq!(app.seed, Some(42));
+        assert_eq!(app.session.unwrap().kind, SessionKind::Drill);
+        assert_eq!(app.drill.unwrap().seed(), 42);
+
+        let recording = Recording::parse("vex-recording 1 0 [\"--nope\"]\n".as_bytes()).unwrap();
+        assert!(recording.app().is_err());
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let path = std::env::temp_dir().join(format!("vex-recording-{}.txt", std::process::id()));
+        let config = Config::parse("[keys]\nreset = \"<C-r>\"").unwrap();
+
+        let args = ["review".to_string()];
+        let progress = Progress::default();
+        let mut recorder = Recorder::create(&path, 3, &args, &config, &progress).unwrap();
+        recorder.record(&Event::Key(Key::ctrl('r'))).unwrap();
+        recorder.finish().unwrap();
+        let recording = Recording::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(recording.args, vec!["review"]);
+        assert_eq!(recording.config, config);
+        assert_eq!(recording.progress, None);
+        assert_eq!(recording.events[0].event, Event::Key(Key::ctrl('r')));
+    }
+
+    #[test]
+    fn test_review_replays_with_the_recorded_progress() {
+        let mut store = ProgressStore::in_memory();
+        let attempt = TaskAttempt {
+            attempts: 1,
+            mistakes: 0,
+            hints_used: 0,
+            elapsed: Duration::from_secs(1),
+        };
+        store.record_task(TaskRef::new(1, 0), attempt, 1);
+        let mut progress = store.data().clone();
+        let task = progress.lessons.get_mut(&1).unwrap().tasks.get_mut(&0).unwrap();
+        task.review.as_mut().unwrap().due = 0;
+
+        let path = std::env::temp_dir().join(format!("vex-review-{}.txt", std::process::id()));
+        let (args, config) = (["review".to_string()], Config::default());
+        let mut recorder = Recorder::create(&path, 3, &args, &config, &progress).unwrap();
+        recorder.record(&Event::Key(Key::char('l'))).unwrap();
+        recorder.finish().unwrap();
+        let recording = Recording::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(recording.progress.as_ref().unwrap().progress, progress);
+        let mut app = recording.app().unwrap();
+        assert_eq!(app.session.as_ref().unwrap().kind, SessionKind::Review);
+        assert_eq!(app.session.as_ref().unwrap().tasks.len(), 1);
+
+        let start = app.clock;
+        for recorded in recording.events.clone() {
+            play_event(&mut app, start, recorded);
+        }
+        assert_eq!(app.progress.lesson(1).unwrap().tasks[&0].completions, 2);
+
+        // Without the progress there is nothing to review.
+        let recording = Recording::parse("vex-recording 1 3 [\"review\"]\n".as_bytes()).unwrap();
+        assert!(recording.app().unwrap().session.is_none());
+    }
+
+    #[test]
+    fn test_player() {
+        let mut player = Player::new(Recording::parse(SAMPLE.as_bytes()).unwrap(), 2.0);
+
+        assert_eq!(player.advance(Duration::from_millis(100)).len(), 1);
+        assert_eq!(player.advance(Duration::from_millis(400)).len(), 3);
+
+        player.toggle_pause();
+        assert!(player.advance(Duration::from_secs(10)).is_empty());
+        let step = player.step().unwrap();
+        assert_eq!(step.event, Event::Key(Key::named(KeyCode::Esc)));
+        assert_eq!(player.position(), Duration::from_millis(step.at));
+        assert!(player.is_finished());
+        assert_eq!(player.step(), None);
+    }
+
+    /// A challenge solved with the optimal keys, one key every `gap`
+    /// milliseconds, and how long each task took.
+    fn challenge_recording(gap: u64) -> (Recording, Vec<Duration>) {
+        let header = "vex-recording 1 9 [\"challenge\",\"hjkl-sprint\"]\n";
+        let mut recording = Recording::parse(header.as_bytes()).unwrap();
+        let app = recording.app().unwrap();
+
+        let mut at = 0;
+        let mut times = Vec::new();
+        for session_task in &app.session.unwrap().tasks {
+            let started = at;
+            for motion in session_task.task.expected_commands.iter().filter_map(|c| c.as_motion()) {
+                for key in Key::parse_sequence(motion.to_key_str()) {
+                    at += gap;
+                    recording.events.push(RecordedEvent { at, event: Event::Key(key) });
+                }
+            }
+            times.push(Duration::from_millis(at - started));
+        }
+        recording.events.push(RecordedEvent { at: at + gap, event: Event::Quit });
+        (recording, times)
+    }
+
+    #[test]
+    fn test_challenge_replays_keep_the_recorded_times() {
+        let (recording, times) = challenge_recording(300);
+        let replay = |speed: f64, step: bool| {
+            let mut app = recording.app().unwrap();
+            let start = app.clock;
+            let mut player = Player::new(recording.clone(), speed);
+            while !player.is_finished() {
+                let due = if step {
+                    player.step().into_iter().collect()
+                } else {
+                    player.advance(Duration::from_millis(16))
+                };
+                for recorded in due {
+                    play_event(&mut app, start, recorded);
+                }
+                app.tick(start + player.position());
+            }
+            app.summary.expect("the challenge is finished")
+        };
+
+        let summary = replay(1.0, false);
+        assert_eq!(summary.solved, times.len());
+        assert_eq!(summary.total_time, times.iter().sum());
+        assert_eq!(replay(4.0, false), summary);
+        assert_eq!(replay(Player::MAX_SPEED, false), summary);
+        assert_eq!(replay(1.0, true), summary);
+    }
+}
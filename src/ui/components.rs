@@ -1,12 +1,18 @@
 use ratatui::{
-    layout::Rect,
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Position, Rect},
+    style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Paragraph, Sparkline, Wrap},
     Frame,
 };
+use unicode_width::UnicodeWidthChar;
 
-use crate::app::{App, AppMode};
+use crate::app::{App, AppMode, LineNumbers};
+use crate::config::Action;
+use crate::learning::{Lesson, MotionReference};
+use crate::ui::highlight::highlighter_for;
+use crate::ui::theme::Theme;
+use crate::vim::CursorPosition;
 
 pub fn render_ui(frame: &mut Frame, app: &App) {
     crate::ui::layout::draw_ui(frame, app);
@@ -16,11 +22,21 @@ pub fn render_header(frame: &mut Frame, area: Rect, app: &App) {
     let mode_text = match app.mode {
         AppMode::Menu => "Menu",
         AppMode::Lesson => "Lesson",
+        AppMode::Summary => "Summary",
+        AppMode::Stats => "Stats",
     };
 
     let lesson_info = if let AppMode::Lesson = app.mode {
         if let Some(lesson) = app.current_lesson() {
-            format!("[Lesson {}/6: {}]", lesson.id, lesson.title)
+            let position = app.curriculum.position(lesson.id).unwrap_or(0) + 1;
+            format!(
+                "[Lesson {}/{}: {}]",
+                position,
+                app.curriculum.lessons().len(),
+                lesson.title
+            )
+        } else if let Some(session) = &app.session {
+            format!("[{}]", session.title)
         } else {
             String::new()
         }
@@ -28,146 +44,512 @@ pub fn render_header(frame: &mut Frame, area: Rect, app: &App) {
         String::new()
     };
 
-    let header_text = format!("VEX - Vim Trainer  |  {}  {}", mode_text, lesson_info);
+    let mut header_text = format!("VEX - Vim Trainer  |  {}  {}", mode_text, lesson_info);
+    if header_text.chars().count() > area.width as usize {
+        header_text = format!("{}  {}", mode_text, lesson_info);
+    }
+
+    if let Some(run) = &app.challenge {
+        header_text.push_str(&format!("  ⏱ {:.1}s", app.clock.duration_since(run.started_at).as_secs_f64()));
+    }
 
     let header = Paragraph::new(header_text)
-        .style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .style(app.theme.title)
         .block(Block::default().borders(Borders::BOTTOM));
 
     frame.render_widget(header, area);
 }
 
-pub fn render_instruction(frame: &mut Frame, area: Rect, app: &App) {
-    let content = match app.mode {
-        AppMode::Menu => {
-            vec![
-                Line::from(Span::styled(
-                    "Welcome to VEX - Vim Movement Trainer",
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                )),
-                Line::from(""),
-                Line::from("Select a lesson to begin:"),
-                Line::from(""),
-                Line::from("  1. Basic Movement (hjkl)"),
-                Line::from("  2. Word Movement (wbe)"),
-                Line::from("  3. Line Extremes (0$)"),
-                Line::from("  4. Combining Movements"),
-                Line::from("  5. Vertical Movement (gg, G)"),
-                Line::from("  6. Complete Movement Practice"),
-                Line::from(""),
-                Line::from("Press 1-6 to select a lesson, or 'q' to quit."),
-            ]
-        }
-        AppMode::Lesson => {
-            if let Some(lesson) = app.current_lesson() {
-                let mut lines = vec![Line::from(Span::styled(
-                    &lesson.title,
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD),
-                ))];
-
-                for line in &lesson.explanation {
-                    lines.push(Line::from(line.as_str()));
-                }
+/// A short progress badge for a lesson in the menu.
+fn lesson_badge(app: &App, lesson: &Lesson) -> Option<Span<'static>> {
+    let progress = app.progress.lesson(lesson.id)?;
 
-                lines.push(Line::from(""));
-                lines.push(Line::from(Span::styled(
-                    "────────────────────────────────────────────────────────",
-                    Style::default().fg(Color::DarkGray),
-                )));
-                lines.push(Line::from(""));
-
-                if let Some(task) = app.current_task() {
-                    lines.push(Line::from(Span::styled(
-                        format!("Task {}/{}: {}", app.current_task_index + 1, lesson.tasks.len(), task.description),
-                        Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-                    )));
-                }
+    if progress.completed {
+        let text = match progress.best_score {
+            Some(score) => format!(" ✓ {}", score),
+            None => " ✓".to_string(),
+        };
+        return Some(Span::styled(text, app.theme.success));
+    }
+
+    let solved = progress
+        .tasks
+        .iter()
+        .filter(|(index, stats)| **index < lesson.tasks.len() && stats.completions > 0)
+        .count();
+    (solved > 0).then(|| {
+        Span::styled(
+            format!(" ◐ {}/{}", solved, lesson.tasks.len()),
+            app.theme.warning,
+        )
+    })
+}
+
+fn menu_columns(area: Rect) -> [Rect; 2] {
+    Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(area)
+}
 
-                lines
+/// The menu's rows, section headings and lessons, with the menu index of
+/// each lesson row.
+fn menu_rows(app: &App) -> (Vec<Line<'static>>, Vec<Option<usize>>) {
+    let mut rows = Vec::new();
+    let mut entries = Vec::new();
+    let mut index = 0;
+
+    for (section, lessons) in app.curriculum.sections() {
+        rows.push(Line::from(Span::styled(section.to_string(), app.theme.heading)));
+        entries.push(None);
+
+        for lesson in lessons {
+            let selected = index == app.menu_index;
+            let marker = if selected { "▶ " } else { "  " };
+            let style = if selected {
+                app.theme.selected
             } else {
-                vec![Line::from("No lesson selected")]
+                Style::default()
+            };
+
+            let mut spans = vec![Span::styled(
+                format!("{}{}. {}", marker, lesson.id, lesson.title),
+                style,
+            )];
+            spans.extend(lesson_badge(app, lesson));
+
+            rows.push(Line::from(spans));
+            entries.push(Some(index));
+            index += 1;
+        }
+    }
+
+    (rows, entries)
+}
+
+/// First menu row to draw so the selected lesson stays visible.
+fn menu_offset(entries: &[Option<usize>], menu_index: usize, height: usize) -> usize {
+    let selected_row = entries
+        .iter()
+        .position(|&entry| entry == Some(menu_index))
+        .unwrap_or(0);
+    viewport_offset(selected_row, entries.len(), height)
+}
+
+/// The menu index of the lesson drawn at a screen cell of the menu in `area`.
+pub fn menu_entry_at(area: Rect, app: &App, column: u16, row: u16) -> Option<usize> {
+    let list = Block::default().borders(Borders::ALL).inner(menu_columns(area)[0]);
+    if !list.contains(Position::new(column, row)) {
+        return None;
+    }
+
+    let (_, entries) = menu_rows(app);
+    let offset = menu_offset(&entries, app.menu_index, list.height as usize);
+    entries
+        .get(offset + (row - list.y) as usize)
+        .copied()
+        .flatten()
+}
+
+fn render_menu(frame: &mut Frame, area: Rect, app: &App) {
+    let columns = menu_columns(area);
+    let (rows, entries) = menu_rows(app);
+    let height = columns[0].height.saturating_sub(2) as usize;
+    let offset = menu_offset(&entries, app.menu_index, height);
+    let visible: Vec<Line> = rows.into_iter().skip(offset).take(height).collect();
+
+    let list = Paragraph::new(visible).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Lessons (profile: {})", app.progress.profile())),
+    );
+    frame.render_widget(list, columns[0]);
+
+    let preview = match app.selected_lesson() {
+        Some(lesson) => {
+            let keys: Vec<&str> = lesson
+                .commands
+                .iter()
+                .filter_map(|c| c.as_motion())
+                .map(|m| m.to_key_str())
+                .collect();
+
+            let mut lines = vec![
+                Line::from(Span::styled(lesson.title.clone(), app.theme.emphasis)),
+                Line::from(Span::styled(
+                    format!("{} tasks  ·  commands: {}", lesson.tasks.len(), keys.join(" ")),
+                    app.theme.muted,
+                )),
+            ];
+
+            if let Some(badge) = lesson_badge(app, lesson) {
+                lines.push(Line::from(vec![Span::raw("Progress:"), badge]));
             }
+
+            lines.push(Line::from(""));
+            lines.extend(lesson.explanation.iter().map(|l| Line::from(l.as_str())));
+            lines
         }
+        None => vec![Line::from("No lessons available")],
     };
 
-    let paragraph = Paragraph::new(content)
-        .block(Block::default().borders(Borders::ALL).title("Instructions"))
+    let preview = Paragraph::new(preview)
+        .block(Block::default().borders(Borders::ALL).title("Preview"))
         .wrap(Wrap { trim: true });
+    frame.render_widget(preview, columns[1]);
+}
+
+/// Draws the lesson instructions. When `compact`, the explanation is left
+/// out and the session title moves into the border to leave room for the
+/// buffer.
+pub fn render_instruction(frame: &mut Frame, area: Rect, app: &App, compact: bool) {
+    if compact
+        && app.mode == AppMode::Lesson
+        && let Some(session) = &app.session
+    {
+        let task = app.current_task().map_or(String::new(), |task| {
+            format!("Task {}/{}: {}", app.current_task_index + 1, session.tasks.len(), task.description)
+        });
+        let paragraph = Paragraph::new(Span::styled(task, app.theme.task))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(Span::styled(session.title.as_str(), app.theme.emphasis)),
+            )
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    if app.mode == AppMode::Menu {
+        render_menu(frame, area, app);
+        return;
+    }
+
+    let block = Block::default().borders(Borders::ALL).title("Instructions");
+    let content = instruction_lines(app, area.width);
+    let scroll = app
+        .instruction_scroll
+        .min(instruction_max_scroll(&content, block.inner(area)));
+    let paragraph = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: true })
+        .scroll((scroll, 0));
 
     frame.render_widget(paragraph, area);
 }
 
-pub fn render_buffer(frame: &mut Frame, area: Rect, app: &App) {
-    if let AppMode::Lesson = app.mode {
-        if let Some(_task) = app.current_task() {
-            let buffer = &app.task_buffer;
-            let cursor = buffer.cursor();
-
-            let mut lines = Vec::new();
-
-            for (line_idx, line_text) in buffer.lines().iter().enumerate() {
-                if line_idx == cursor.line {
-                    let mut spans = Vec::new();
-
-                    for (col_idx, ch) in line_text.chars().enumerate() {
-                        if col_idx == cursor.column {
-                            spans.push(Span::styled(
-                                ch.to_string(),
-                                Style::default()
-                                    .fg(Color::Black)
-                                    .bg(Color::Green)
-                                    .add_modifier(Modifier::BOLD),
-                            ));
-                        } else {
-                            spans.push(Span::raw(ch.to_string()));
-                        }
-                    }
+fn instruction_lines(app: &App, width: u16) -> Vec<Line<'_>> {
+    if app.mode != AppMode::Lesson {
+        return Vec::new();
+    }
+    let Some(session) = &app.session else {
+        return vec![Line::from("No lesson selected")];
+    };
 
-                    if cursor.column >= line_text.len() {
-                        spans.push(Span::styled(
-                            " ",
-                            Style::default()
-                                .fg(Color::Black)
-                                .bg(Color::Green)
-                                .add_modifier(Modifier::BOLD),
-                        ));
-                    }
+    let mut lines = vec![Line::from(Span::styled(&session.title, app.theme.emphasis))];
 
-                    if buffer.line_count() > 1 {
-                        let line_num = format!("{:2} │ ", line_idx + 1);
-                        let mut line_spans = vec![Span::styled(
-                            line_num,
-                            Style::default().fg(Color::DarkGray),
-                        )];
-                        line_spans.extend(spans);
-                        lines.push(Line::from(line_spans));
-                    } else {
-                        lines.push(Line::from(spans));
-                    }
+    if let Some(lesson) = app.current_lesson() {
+        for line in &lesson.explanation {
+            lines.push(Line::from(line.as_str()));
+        }
+    } else if let Some(origin) = session
+        .tasks
+        .get(app.current_task_index)
+        .and_then(|t| t.origin)
+        .and_then(|origin| app.curriculum.get_lesson(origin.lesson_id))
+    {
+        lines.push(Line::from(format!(
+            "Reviewing lesson {}: {}",
+            origin.id, origin.title
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "─".repeat(width.saturating_sub(2).min(56) as usize),
+        app.theme.muted,
+    )));
+    lines.push(Line::from(""));
+
+    if let Some(task) = app.current_task() {
+        lines.push(Line::from(Span::styled(
+            format!("Task {}/{}: {}", app.current_task_index + 1, session.tasks.len(), task.description),
+            app.theme.task,
+        )));
+    }
+
+    lines
+}
+
+/// How far the instructions can scroll before their last line reaches the
+/// top of `inner`. Wrapped lines are estimated from their width.
+fn instruction_max_scroll(lines: &[Line], inner: Rect) -> u16 {
+    let width = inner.width.max(1) as usize;
+    let wrapped: usize = lines.iter().map(|line| line.width().div_ceil(width).max(1)).sum();
+    wrapped.saturating_sub(inner.height as usize) as u16
+}
+
+/// How far the instructions in `area` can scroll; compact instructions
+/// never scroll.
+pub fn instruction_scroll_limit(area: Rect, app: &App, compact: bool) -> u16 {
+    if compact {
+        return 0;
+    }
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    instruction_max_scroll(&instruction_lines(app, area.width), inner)
+}
+
+/// Per-cell highlights layered over the buffer text.
+struct BufferOverlay<'a> {
+    theme: &'a Theme,
+    cursor: CursorPosition,
+    target: Option<CursorPosition>,
+    guess: Option<CursorPosition>,
+    trail: Vec<CursorPosition>,
+    solution: Vec<CursorPosition>,
+}
+
+impl<'a> BufferOverlay<'a> {
+    fn new(app: &'a App) -> Self {
+        // A quiz's target is its answer, so it stays hidden until solved.
+        let target = app
+            .current_task()
+            .filter(|task| {
+                if task.is_prediction() {
+                    app.task_completed
                 } else {
-                    if buffer.line_count() > 1 {
-                        let line_num = format!("{:2} │ ", line_idx + 1);
-                        lines.push(Line::from(vec![
-                            Span::styled(line_num, Style::default().fg(Color::DarkGray)),
-                            Span::raw(line_text.clone()),
-                        ]));
-                    } else {
-                        lines.push(Line::from(line_text.clone()));
+                    app.overlays.target
+                }
+            })
+            .map(|task| task.target_position);
+        let trail = if app.overlays.trail {
+            app.trail.clone()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            theme: &app.theme,
+            cursor: app.task_buffer.cursor(),
+            target,
+            guess: app.guess,
+            trail,
+            solution: app.solution_path().unwrap_or_default(),
+        }
+    }
+
+    /// Style for one cell. The cursor always wins, then the target, the quiz
+    /// guess, the optimal path, and the learner's trail, which fades with age.
+    fn style(&self, position: CursorPosition) -> Option<Style> {
+        if position == self.cursor {
+            return Some(self.theme.cursor);
+        }
+
+        if self.target == Some(position) {
+            return Some(self.theme.target);
+        }
+
+        if self.guess == Some(position) {
+            return Some(self.theme.guess);
+        }
+
+        if self.solution.contains(&position) {
+            return Some(self.theme.solution);
+        }
+
+        let age = self.trail.iter().rev().position(|&p| p == position)?;
+        Some(match age {
+            0..=2 => self.theme.trail[0],
+            3..=6 => self.theme.trail[1],
+            _ => self.theme.trail[2],
+        })
+    }
+}
+
+/// Where the buffer pane in `area` draws the buffer: shared by rendering
+/// and mouse hit testing.
+pub struct BufferView {
+    pub text_area: Rect,
+    /// First buffer line shown.
+    pub offset: usize,
+    /// Largest offset that still fills the pane.
+    pub max_offset: usize,
+    /// First display column shown. Long lines are not wrapped; the view
+    /// scrolls sideways to keep the cursor (or the quiz guess) on screen.
+    pub left: usize,
+    /// Display columns left for the text after the gutter.
+    pub text_width: usize,
+    gutter_width: usize,
+}
+
+impl BufferView {
+    pub fn new(area: Rect, app: &App) -> Self {
+        let inner = Block::default().borders(Borders::ALL).inner(area);
+        let [text_area, _status_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let height = text_area.height as usize;
+        let line_count = app.task_buffer.line_count();
+        let max_offset = line_count.saturating_sub(height);
+        let offset = match app.buffer_scroll {
+            Some(scroll) => scroll.min(max_offset),
+            None => viewport_offset(app.task_buffer.cursor().line, line_count, height),
+        };
+        let gutter_width = line_count.to_string().len().max(2);
+        let gutter_columns =
+            gutter_label(app.line_numbers, 0, 0, gutter_width).map_or(0, |label| label.chars().count());
+        let text_width = (text_area.width as usize).saturating_sub(gutter_columns);
+        let focus = app.guess.unwrap_or(app.task_buffer.cursor());
+        let focus_line = app.task_buffer.get_line(focus.line).unwrap_or("");
+
+        Self {
+            text_area,
+            offset,
+            max_offset,
+            left: horizontal_offset(focus_line, focus.column, text_width),
+            text_width,
+            gutter_width,
+        }
+    }
+
+    /// Columns taken by the line number gutter, including the separator.
+    fn gutter_columns(&self, numbers: LineNumbers) -> usize {
+        gutter_label(numbers, 0, 0, self.gutter_width).map_or(0, |label| label.chars().count())
+    }
+
+    /// The buffer cell drawn at a screen position, going by display width so
+    /// that both halves of a wide character find it. Clicks past the end of
+    /// a line land on its last character.
+    pub fn cell_at(&self, app: &App, column: u16, row: u16) -> Option<CursorPosition> {
+        if !self.text_area.contains(Position::new(column, row)) {
+            return None;
+        }
+
+        let line = self.offset + (row - self.text_area.y) as usize;
+        let text = app.task_buffer.lines().get(line)?;
+        let x = ((column - self.text_area.x) as usize)
+            .checked_sub(self.gutter_columns(app.line_numbers))?
+            + self.left;
+
+        let mut end = 0;
+        let column = text.chars().position(|ch| {
+            end += char_width(ch);
+            x < end
+        });
+        Some(CursorPosition::new(
+            line,
+            column.unwrap_or(text.chars().count().saturating_sub(1)),
+        ))
+    }
+}
+
+/// Display columns a character takes on screen, as ratatui draws it.
+fn char_width(ch: char) -> usize {
+    ch.width().unwrap_or(0)
+}
+
+/// First display column to draw so that the character at `column` of
+/// `text` is on screen: 0 while it fits, otherwise the character is put in
+/// the middle, as Vim does with 'nowrap'.
+fn horizontal_offset(text: &str, column: usize, width: usize) -> usize {
+    let start: usize = text.chars().take(column).map(char_width).sum();
+    let end = start + text.chars().nth(column).map_or(1, char_width).max(1);
+    if end <= width {
+        0
+    } else {
+        start.saturating_sub(width / 2)
+    }
+}
+
+pub fn render_buffer(frame: &mut Frame, area: Rect, app: &App) {
+    if let AppMode::Lesson = app.mode
+        && let Some(_task) = app.current_task()
+    {
+        let buffer = &app.task_buffer;
+        let cursor = buffer.cursor();
+        let overlay = BufferOverlay::new(app);
+        let block = Block::default().borders(Borders::ALL);
+        let inner = block.inner(area);
+        let [text_area, status_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let view = BufferView::new(area, app);
+        let height = text_area.height as usize;
+        let offset = view.offset;
+        let language = app.current_task().map(|t| t.language).unwrap_or_default();
+        let tokens = highlighter_for(language).highlight(buffer.lines());
+
+        let mut lines = Vec::new();
+
+        for (line_idx, line_text) in buffer
+            .lines()
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(height.max(1))
+        {
+            let mut spans = Vec::new();
+
+            if let Some(label) = gutter_label(app.line_numbers, line_idx, cursor.line, view.gutter_width) {
+                spans.push(Span::styled(label, app.theme.gutter));
+            }
+
+            let len = line_text.chars().count();
+            let mut end = 0;
+            for (col_idx, ch) in line_text.chars().enumerate() {
+                let start = end;
+                end += char_width(ch);
+                // Characters scrolled off to the left, and the right half of
+                // a wide one cut by the edge.
+                if start < view.left {
+                    if end > view.left {
+                        spans.push(Span::raw(" ".repeat(end - view.left)));
                     }
+                    continue;
                 }
+                if end > view.left + view.text_width {
+                    break;
+                }
+
+                let position = CursorPosition::new(line_idx, col_idx);
+                let token_style = tokens
+                    .get(line_idx)
+                    .and_then(|kinds| kinds.get(col_idx))
+                    .map_or(Style::default(), |&kind| app.theme.token(kind));
+                let style = match overlay.style(position) {
+                    Some(style) => token_style.patch(style),
+                    None => token_style,
+                };
+                spans.push(Span::styled(ch.to_string(), style));
             }
 
-            let paragraph = Paragraph::new(lines)
-                .block(Block::default().borders(Borders::ALL).title("Buffer"))
-                .wrap(Wrap { trim: false });
+            // A cursor or target past the end of the line (e.g. on an empty
+            // line) is drawn as a highlighted space.
+            let past_end = [Some(overlay.cursor), overlay.target, overlay.guess]
+                .into_iter()
+                .flatten()
+                .find(|p| p.line == line_idx && p.column >= len);
+            if let Some(position) = past_end
+                && end >= view.left
+                && let Some(style) = overlay.style(position)
+            {
+                spans.push(Span::styled(" ", style));
+            }
 
-            frame.render_widget(paragraph, area);
-            return;
+            lines.push(Line::from(spans));
         }
+
+        let title = if buffer.line_count() > height {
+            format!("Buffer (lines {}-{} of {})", offset + 1, (offset + height).min(buffer.line_count()), buffer.line_count())
+        } else {
+            "Buffer".to_string()
+        };
+
+        let paragraph = Paragraph::new(lines);
+
+        frame.render_widget(block.title(title), area);
+        frame.render_widget(paragraph, text_area);
+        frame.render_widget(
+            Paragraph::new(status_line(app, offset, height, status_area.width as usize)),
+            status_area,
+        );
+        return;
     }
 
     let empty = Paragraph::new("No buffer to display")
@@ -175,41 +557,284 @@ pub fn render_buffer(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(empty, area);
 }
 
+/// The gutter text for one buffer line, or `None` when numbers are off.
+fn gutter_label(numbers: LineNumbers, line: usize, cursor_line: usize, width: usize) -> Option<String> {
+    let relative = line.abs_diff(cursor_line);
+    match numbers {
+        LineNumbers::None => None,
+        LineNumbers::Absolute => Some(format!("{:>width$} │ ", line + 1)),
+        LineNumbers::Relative => Some(format!("{:>width$} │ ", relative)),
+        LineNumbers::Hybrid if relative == 0 => Some(format!("{:<width$} │ ", line + 1)),
+        LineNumbers::Hybrid => Some(format!("{:>width$} │ ", relative)),
+    }
+}
+
+/// Where the viewport sits in the file, as in Vim's ruler: `All`, `Top`,
+/// `Bot` or the percentage of lines above the window.
+fn ruler_position(offset: usize, height: usize, line_count: usize) -> String {
+    let below = line_count.saturating_sub(offset + height);
+    match (offset, below) {
+        (0, 0) => "All".to_string(),
+        (0, _) => "Top".to_string(),
+        (_, 0) => "Bot".to_string(),
+        (above, below) => format!("{}%", above * 100 / (above + below)),
+    }
+}
+
+/// A Vim-style status line: mode, pending keys, then `line:col` and the
+/// ruler position on the right.
+fn status_line(app: &App, offset: usize, height: usize, width: usize) -> Line<'static> {
+    let cursor = app.task_buffer.cursor();
+    let mode = " NORMAL ";
+    // Like Vim's 'showcmd': a half-typed command, binding or mapping.
+    let unresolved: String = app
+        .pending_keys
+        .iter()
+        .chain(&app.mapper.pending_keys())
+        .map(|key| key.to_string())
+        .collect();
+    let pending = format!(" {}{}", app.command_parser.pending_keys(), unresolved);
+    let ruler = format!(
+        "{}:{}  {:>3} ",
+        cursor.line + 1,
+        cursor.column + 1,
+        ruler_position(offset, height, app.task_buffer.line_count())
+    );
+    let used = mode.chars().count() + pending.chars().count() + ruler.chars().count();
+
+    Line::from(vec![
+        Span::styled(mode, app.theme.status_mode),
+        Span::styled(pending, app.theme.status_line),
+        Span::styled(" ".repeat(width.saturating_sub(used)), app.theme.status_line),
+        Span::styled(ruler, app.theme.status_line),
+    ])
+}
+
+/// First buffer line to draw so the cursor line stays near the middle of a
+/// viewport `height` lines tall.
+fn viewport_offset(cursor_line: usize, line_count: usize, height: usize) -> usize {
+    if line_count <= height {
+        return 0;
+    }
+
+    cursor_line
+        .saturating_sub(height / 2)
+        .min(line_count - height)
+}
+
+pub fn render_summary(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(summary) = &app.summary else {
+        return;
+    };
+
+    let label = app.theme.label;
+    let value = app.theme.value;
+    let stat = |name: &str, text: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<22}", name), label),
+            Span::styled(text, value),
+        ])
+    };
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("Challenge complete: {}", summary.challenge),
+            app.theme.emphasis,
+        )),
+        Line::from(""),
+        stat("Score", summary.score.to_string()),
+        stat(
+            "Accuracy",
+            format!("{}/{} ({:.0}%)", summary.solved, summary.tasks, summary.accuracy() * 100.0),
+        ),
+        stat("Total time", format!("{:.1}s", summary.total_time.as_secs_f64())),
+        stat("Average per task", format!("{:.1}s", summary.average_time.as_secs_f64())),
+        stat("Keystroke efficiency", format!("{:.0}%", summary.efficiency * 100.0)),
+    ];
+
+    if summary.new_best {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("  ★ New personal best!", app.theme.success)));
+    } else if let Some(best) = app.progress.challenge_best(&summary.challenge) {
+        lines.push(stat("Personal best", best.score.to_string()));
+    }
+
+    if !summary.slowest_motions.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Slowest motions:"));
+        for (motion, average) in summary.slowest_motions.iter().take(3) {
+            lines.push(stat(
+                &format!("{:?} ({})", motion, motion.to_key_str()),
+                format!("{:.1}s per task", average.as_secs_f64()),
+            ));
+        }
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Results"))
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+pub fn render_stats(frame: &mut Frame, area: Rect, app: &App) {
+    let stats = app.stats();
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+    let [chart_area, details_area] =
+        Layout::vertical([Constraint::Length(6), Constraint::Min(0)]).areas(columns[1]);
+
+    let lessons: Vec<Line> = stats
+        .lessons
+        .iter()
+        .map(|lesson| {
+            const BAR_WIDTH: usize = 10;
+            let filled = (lesson.solved * BAR_WIDTH).checked_div(lesson.total).unwrap_or(0);
+            let mut spans = vec![
+                Span::raw(format!("{}. {:<28} ", lesson.lesson_id, lesson.title)),
+                Span::styled("█".repeat(filled), app.theme.success),
+                Span::styled("░".repeat(BAR_WIDTH - filled), app.theme.muted),
+                Span::raw(format!(" {}/{}", lesson.solved, lesson.total)),
+            ];
+            if lesson.completed {
+                let score = lesson.best_score.map_or(String::new(), |s| format!(" {}", s));
+                spans.push(Span::styled(format!("  ✓{}", score), app.theme.success));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(lessons).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Lessons (profile: {})", app.progress.profile())),
+        ),
+        columns[0],
+    );
+
+    let accuracy: Vec<Option<u64>> = stats
+        .daily_accuracy
+        .iter()
+        .map(|day| day.map(|a| (a * 100.0).round() as u64))
+        .collect();
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title("Accuracy (30 days)"))
+            .data(accuracy)
+            .max(100)
+            .style(app.theme.success)
+            .absent_value_style(app.theme.muted)
+            .absent_value_symbol("·"),
+        chart_area,
+    );
+
+    let label = app.theme.label;
+    let value = app.theme.value;
+    let stat = |name: &str, text: String| {
+        Line::from(vec![
+            Span::styled(format!("  {:<22}", name), label),
+            Span::styled(text, value),
+        ])
+    };
+    let percent = |ratio: f64| format!("{:.0}%", ratio * 100.0);
+
+    let mut lines = vec![
+        stat("Tasks solved", stats.tasks_solved.to_string()),
+        stat(
+            "Keystroke efficiency",
+            stats.efficiency.map_or("-".to_string(), percent),
+        ),
+        stat("Streak", format!("{} day(s)", stats.streak_days)),
+        stat(
+            "Hints used",
+            format!("{} (on {} task(s))", stats.hints_used, stats.tasks_with_hints),
+        ),
+        Line::from(""),
+        Line::from(Span::styled("Most-missed motions:", app.theme.emphasis)),
+    ];
+    if stats.most_missed.is_empty() {
+        lines.push(Line::from(Span::styled("  None yet", app.theme.muted)));
+    }
+    for motion in stats.most_missed.iter().take(5) {
+        lines.push(stat(
+            &format!("{:?} ({})", motion.motion, motion.motion.to_key_str()),
+            format!(
+                "missed on {} of {} task(s) ({})",
+                motion.missed,
+                motion.tasks,
+                percent(motion.miss_rate())
+            ),
+        ));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Statistics"))
+            .wrap(Wrap { trim: false }),
+        details_area,
+    );
+}
+
 pub fn render_feedback(frame: &mut Frame, area: Rect, app: &App) {
     let mut lines = Vec::new();
 
     lines.push(Line::from(vec![
-        Span::styled("Input: ", Style::default().fg(Color::Gray)),
-        Span::styled(
-            &app.input_buffer,
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
-        ),
+        Span::styled("Input: ", app.theme.label),
+        Span::styled(&app.input_buffer, app.theme.value),
     ]));
 
-    lines.push(Line::from(""));
-
     if !app.feedback_message.is_empty() {
         let style = if app.feedback_message.contains("Correct") || app.feedback_message.contains("completed") {
-            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+            app.theme.success
         } else if app.feedback_message.contains("Incorrect") || app.feedback_message.contains("Wrong") {
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+            app.theme.error
         } else {
-            Style::default().fg(Color::Yellow)
+            app.theme.warning
         };
 
         lines.push(Line::from(Span::styled(&app.feedback_message, style)));
     }
 
-    if app.show_hint {
-        if let Some(task) = app.current_task() {
-            let hint_index = app.hint_index.min(task.hints.len().saturating_sub(1));
-            if hint_index < task.hints.len() {
-                lines.push(Line::from(""));
-                lines.push(Line::from(Span::styled(
-                    format!("Hint: {}", task.hints[hint_index]),
-                    Style::default().fg(Color::Cyan),
-                )));
-            }
+    // Quizzes are answered without typing, so there is no path to compare.
+    if app.task_completed
+        && app.overlays.solution
+        && let Some(task) = app.current_task().filter(|task| !task.is_prediction())
+    {
+        let keys = |commands: &[crate::vim::Command]| -> String {
+            commands
+                .iter()
+                .filter_map(|c| c.as_motion())
+                .map(|m| m.to_key_str())
+                .collect()
+        };
+
+        lines.push(Line::from(vec![
+            Span::styled("Your path: ", app.theme.your_path),
+            Span::raw(format!(
+                "{} ({})   ",
+                keys(&app.executed_commands),
+                app.executed_commands.len()
+            )),
+            Span::styled("Optimal: ", app.theme.optimal_path),
+            Span::raw(format!(
+                "{} ({})",
+                keys(&task.expected_commands),
+                task.expected_commands.len()
+            )),
+        ]));
+    }
+
+    if app.show_hint
+        && let Some(task) = app.current_task()
+    {
+        let hint_index = app.hint_index.min(task.hints.len().saturating_sub(1));
+        if hint_index < task.hints.len() {
+            lines.push(Line::from(Span::styled(
+                format!("Hint: {}", task.hints[hint_index]),
+                app.theme.hint,
+            )));
         }
     }
 
@@ -220,15 +845,228 @@ pub fn render_feedback(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(paragraph, area);
 }
 
+/// Lines of a small sample buffer with the cursor highlighted.
+fn sample_lines(buffer: &crate::vim::Buffer, app: &App) -> Vec<Line<'static>> {
+    let cursor = buffer.cursor();
+    buffer
+        .lines()
+        .iter()
+        .enumerate()
+        .map(|(line_idx, text)| {
+            let mut spans: Vec<Span> = text
+                .chars()
+                .enumerate()
+                .map(|(col_idx, ch)| {
+                    let style = if CursorPosition::new(line_idx, col_idx) == cursor {
+                        app.theme.cursor
+                    } else {
+                        Style::default()
+                    };
+                    Span::styled(ch.to_string(), style)
+                })
+                .collect();
+            if cursor.line == line_idx && cursor.column >= text.chars().count() {
+                spans.push(Span::styled(" ", app.theme.cursor));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// The details pane for one motion, alternating between its example buffer
+/// before and after the motion every second.
+fn help_details(entry: &MotionReference, app: &App, show_after: bool) -> Vec<Line<'static>> {
+    let keys = entry.motion.to_key_str();
+    let before = entry.before(&app.options);
+    let after = entry.after(&app.options);
+    let position = |p: CursorPosition| format!("{}:{}", p.line + 1, p.column + 1);
+
+    let mut lines = vec![
+        Line::from(Span::styled(format!("{}  {}", keys, entry.name), app.theme.emphasis)),
+        Line::from(entry.description),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Example: ", app.theme.label),
+            Span::raw(format!(
+                "press {} to move from {} to {}",
+                keys,
+                position(before.cursor()),
+                position(after.cursor())
+            )),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled(
+            if show_after { "After:" } else { "Before:" },
+            app.theme.muted,
+        )),
+    ];
+    lines.extend(sample_lines(if show_after { &after } else { &before }, app));
+    lines
+}
+
+pub fn render_help(frame: &mut Frame, area: Rect, app: &App) {
+    let Some(help) = &app.help else {
+        return;
+    };
+    let entries = app.help_entries();
+    let selected = help.selected.min(entries.len().saturating_sub(1));
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(area);
+
+    let search = if help.searching {
+        format!("/{}_", help.query)
+    } else if help.query.is_empty() {
+        "[/] to search".to_string()
+    } else {
+        format!("/{}", help.query)
+    };
+    let mut rows = vec![Line::from(Span::styled(search, app.theme.muted))];
+    for (index, entry) in entries.iter().enumerate() {
+        let style = if index == selected {
+            app.theme.selected
+        } else {
+            Style::default()
+        };
+        rows.push(Line::from(Span::styled(
+            format!("{:<3} {}", entry.motion.to_key_str(), entry.name),
+            style,
+        )));
+    }
+    if entries.is_empty() {
+        rows.push(Line::from("No matching motions"));
+    }
+
+    let height = columns[0].height.saturating_sub(3) as usize;
+    let offset = viewport_offset(selected, entries.len(), height);
+    let visible: Vec<Line> = rows
+        .iter()
+        .take(1)
+        .chain(rows.iter().skip(1 + offset).take(height))
+        .cloned()
+        .collect();
+
+    let title = if help.lesson_only {
+        "Motions (current lesson)"
+    } else {
+        "Motions"
+    };
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(visible).block(Block::default().borders(Borders::ALL).title(title)),
+        columns[0],
+    );
+
+    let show_after = (help.opened_at.elapsed().as_millis() / 1000) % 2 == 1;
+    let details = entries
+        .get(selected)
+        .map(|entry| help_details(entry, app, show_after))
+        .unwrap_or_default();
+    frame.render_widget(
+        Paragraph::new(details)
+            .block(Block::default().borders(Borders::ALL).title("Help"))
+            .wrap(Wrap { trim: false }),
+        columns[1],
+    );
+}
+
 pub fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
-    let footer_text = match app.mode {
-        AppMode::Menu => "[1-6] Select Lesson  |  [q] Quit",
-        AppMode::Lesson => "[r] Reset Task  |  [?] Hint  |  [ESC] Menu  |  [Ctrl+Q] Quit",
+    if let Some(command) = &app.command_line {
+        let footer = Paragraph::new(format!(":{}", command))
+            .style(app.theme.value)
+            .block(Block::default().borders(Borders::TOP));
+        frame.render_widget(footer, area);
+        return;
+    }
+
+    // Each mode has footers from most to least detailed; the first that
+    // fits is shown. The last one fits the minimum terminal width.
+    let variants: Vec<String> = match app.mode {
+        _ if app.help.is_some() => vec![
+            "[j/k] Move  |  [/] Search  |  [f] Current lesson only  |  [ESC] Close".to_string(),
+            "[j/k] Move [/] Search [f] Lesson only [ESC] Close".to_string(),
+        ],
+        AppMode::Menu => vec![
+            "[j/k] Move  |  [Enter] Start  |  [F1] Help  |  [r] Review  |  [d] Drill  |  [c/C] Challenge  |  [s] Stats  |  [q] Quit".to_string(),
+            "[Enter] Start [r]eview [d]rill [c]hallenge [s]tats [F1] Help [q]uit".to_string(),
+            "[Enter] Start [r]eview [d]rill [c]hallenge [s]tats [q]uit".to_string(),
+        ],
+        AppMode::Lesson => {
+            let [reset, hint, target, trail, solution, numbers] = [
+                Action::Reset,
+                Action::Hint,
+                Action::ToggleTarget,
+                Action::ToggleTrail,
+                Action::ToggleSolution,
+                Action::CycleNumbers,
+            ]
+            .map(|action| app.bindings.label(action));
+            vec![
+                format!(
+                    "[{}] Reset  |  [{}] Hint  |  [{}] Target  |  [{}] Trail  |  [{}] Solution  |  [{}] Numbers  |  [F1] Help  |  [ESC] Menu  |  [Ctrl+Q] Quit",
+                    reset, hint, target, trail, solution, numbers
+                ),
+                format!(
+                    "[{}] Reset [{}] Hint [{}/{}/{}] Overlays [{}] Numbers [F1] Help [ESC] Menu",
+                    reset, hint, target, trail, solution, numbers
+                ),
+                format!(
+                    "[{}] Reset [{}] Hint [{}/{}/{}/{}] View [F1] Help [ESC] Menu",
+                    reset, hint, target, trail, solution, numbers
+                ),
+            ]
+        }
+        AppMode::Summary | AppMode::Stats => vec!["[Enter] Menu  |  [Ctrl+Q] Quit".to_string()],
     };
+    let footer_text = variants
+        .iter()
+        .find(|text| text.chars().count() <= area.width as usize)
+        .or(variants.last())
+        .cloned()
+        .unwrap_or_default();
 
     let footer = Paragraph::new(footer_text)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(app.theme.muted)
         .block(Block::default().borders(Borders::TOP));
 
     frame.render_widget(footer, area);
 }
+
+/// The playback position and controls of `vex replay`, drawn over the right
+/// end of the top row. The controls are left out when they do not fit.
+pub fn render_replay_status(frame: &mut Frame, app: &App, status: &str) {
+    let area = frame.area();
+    let full = format!(" REPLAY {}  [Space] Pause [n] Step [+/-] Speed [q] Quit ", status);
+    let short = format!(" REPLAY {} ", status);
+    let text = if full.chars().count() * 2 <= area.width as usize { full } else { short };
+
+    let width = (text.chars().count() as u16).min(area.width);
+    let bar = Rect::new(area.right() - width, area.y, width, 1.min(area.height));
+    frame.render_widget(Clear, bar);
+    frame.render_widget(Paragraph::new(text).style(app.theme.status_mode), bar);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gutter_labels() {
+        assert_eq!(gutter_label(LineNumbers::None, 3, 5, 2), None);
+        assert_eq!(gutter_label(LineNumbers::Absolute, 3, 5, 2).unwrap(), " 4 │ ");
+        assert_eq!(gutter_label(LineNumbers::Relative, 3, 5, 2).unwrap(), " 2 │ ");
+        assert_eq!(gutter_label(LineNumbers::Relative, 5, 5, 2).unwrap(), " 0 │ ");
+        assert_eq!(gutter_label(LineNumbers::Hybrid, 7, 5, 2).unwrap(), " 2 │ ");
+        assert_eq!(gutter_label(LineNumbers::Hybrid, 5, 5, 2).unwrap(), "6  │ ");
+    }
+
+    #[test]
+    fn test_ruler_position() {
+        assert_eq!(ruler_position(0, 10, 5), "All");
+        assert_eq!(ruler_position(0, 10, 30), "Top");
+        assert_eq!(ruler_position(20, 10, 30), "Bot");
+        assert_eq!(ruler_position(5, 10, 30), "25%");
+    }
+}
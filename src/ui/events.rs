@@ -1,19 +1,30 @@
-use crossterm::event::{self, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{
+    self, KeyCode as TermKeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
 use std::time::Duration;
 
+use crate::vim::{Key, KeyCode, Modifiers};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Event {
-    Key(char),
-    SpecialKey(SpecialKey),
+    Key(Key),
     Quit,
-    Resize,
+    /// The terminal's new width and height.
+    Resize(u16, u16),
+    /// A mouse action at a screen cell.
+    Mouse {
+        action: MouseAction,
+        column: u16,
+        row: u16,
+    },
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum SpecialKey {
-    Escape,
-    Enter,
-    Backspace,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseAction {
+    Click,
+    ScrollUp,
+    ScrollDown,
 }
 
 pub struct EventHandler;
@@ -26,8 +37,9 @@ impl EventHandler {
     pub fn poll_event(&self, timeout: Duration) -> std::io::Result<Option<Event>> {
         if event::poll(timeout)? {
             match event::read()? {
-                event::Event::Key(key_event) => Ok(Some(self.handle_key_event(key_event))),
-                event::Event::Resize(_, _) => Ok(Some(Event::Resize)),
+                event::Event::Key(key_event) => Ok(self.handle_key_event(key_event)),
+                event::Event::Resize(width, height) => Ok(Some(Event::Resize(width, height))),
+                event::Event::Mouse(mouse_event) => Ok(self.handle_mouse_event(mouse_event)),
                 _ => Ok(None),
             }
         } else {
@@ -35,21 +47,57 @@ impl EventHandler {
         }
     }
 
-    fn handle_key_event(&self, key: KeyEvent) -> Event {
-        if key.modifiers.contains(KeyModifiers::CONTROL) {
-            match key.code {
-                KeyCode::Char('c') | KeyCode::Char('q') => return Event::Quit,
-                _ => {}
-            }
-        }
+    /// Maps left clicks and wheel scrolls; other mouse events are ignored.
+    fn handle_mouse_event(&self, mouse: MouseEvent) -> Option<Event> {
+        let action = match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => MouseAction::Click,
+            MouseEventKind::ScrollUp => MouseAction::ScrollUp,
+            MouseEventKind::ScrollDown => MouseAction::ScrollDown,
+            _ => return None,
+        };
 
-        match key.code {
-            KeyCode::Char(c) => Event::Key(c),
-            KeyCode::Esc => Event::SpecialKey(SpecialKey::Escape),
-            KeyCode::Enter => Event::SpecialKey(SpecialKey::Enter),
-            KeyCode::Backspace => Event::SpecialKey(SpecialKey::Backspace),
-            _ => Event::SpecialKey(SpecialKey::Escape),
+        Some(Event::Mouse {
+            action,
+            column: mouse.column,
+            row: mouse.row,
+        })
+    }
+
+    /// Maps a key press to a `Key`; releases and keys VEX has no name for
+    /// (media keys, lone modifiers) are ignored.
+    fn handle_key_event(&self, key: KeyEvent) -> Option<Event> {
+        if key.kind == KeyEventKind::Release {
+            return None;
         }
+
+        let modifiers = Modifiers {
+            ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+            alt: key.modifiers.contains(KeyModifiers::ALT),
+            shift: key.modifiers.contains(KeyModifiers::SHIFT),
+        };
+
+        let code = match key.code {
+            TermKeyCode::Char(c) if modifiers.ctrl && (c == 'c' || c == 'q') => return Some(Event::Quit),
+            TermKeyCode::Char(c) => KeyCode::Char(c),
+            TermKeyCode::Esc => KeyCode::Esc,
+            TermKeyCode::Enter => KeyCode::Enter,
+            TermKeyCode::Backspace => KeyCode::Backspace,
+            TermKeyCode::Tab | TermKeyCode::BackTab => KeyCode::Tab,
+            TermKeyCode::Up => KeyCode::Up,
+            TermKeyCode::Down => KeyCode::Down,
+            TermKeyCode::Left => KeyCode::Left,
+            TermKeyCode::Right => KeyCode::Right,
+            TermKeyCode::Home => KeyCode::Home,
+            TermKeyCode::End => KeyCode::End,
+            TermKeyCode::PageUp => KeyCode::PageUp,
+            TermKeyCode::PageDown => KeyCode::PageDown,
+            TermKeyCode::Insert => KeyCode::Insert,
+            TermKeyCode::Delete => KeyCode::Delete,
+            TermKeyCode::F(n) => KeyCode::F(n),
+            _ => return None,
+        };
+
+        Some(Event::Key(Key::new(code, modifiers)))
     }
 }
 
@@ -0,0 +1,283 @@
+use crate::learning::Language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    Type,
+    String,
+    Number,
+    Comment,
+    Punctuation,
+}
+
+/// Assigns a token kind to every character of a buffer.
+pub trait Highlighter {
+    /// Returns one `Vec` per line with one kind per character.
+    fn highlight(&self, lines: &[String]) -> Vec<Vec<TokenKind>>;
+}
+
+/// Leaves every character plain.
+pub struct PlainHighlighter;
+
+impl Highlighter for PlainHighlighter {
+    fn highlight(&self, lines: &[String]) -> Vec<Vec<TokenKind>> {
+        lines
+            .iter()
+            .map(|line| vec![TokenKind::Plain; line.chars().count()])
+            .collect()
+    }
+}
+
+/// A small hand-written tokenizer covering keywords, strings, numbers and
+/// comments. It is deliberately forgiving rather than a full lexer.
+pub struct SimpleHighlighter {
+    keywords: &'static [&'static str],
+    line_comment: &'static str,
+    block_comment: Option<(&'static str, &'static str)>,
+    string_quotes: &'static [char],
+    /// Rust uses `'` for lifetimes as well as char literals.
+    char_literals_only: bool,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true",
+    "type", "unsafe", "use", "where", "while",
+];
+
+const JAVASCRIPT_KEYWORDS: &[&str] = &[
+    "async", "await", "break", "case", "catch", "class", "const", "continue", "default",
+    "delete", "do", "else", "export", "extends", "false", "finally", "for", "function", "if",
+    "import", "in", "instanceof", "let", "new", "null", "of", "return", "super", "switch",
+    "this", "throw", "true", "try", "typeof", "undefined", "var", "void", "while", "yield",
+];
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class",
+    "continue", "def", "del", "elif", "else", "except", "finally", "for", "from", "global",
+    "if", "import", "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return",
+    "self", "try", "while", "with", "yield",
+];
+
+impl SimpleHighlighter {
+    pub fn rust() -> Self {
+        Self {
+            keywords: RUST_KEYWORDS,
+            line_comment: "//",
+            block_comment: Some(("/*", "*/")),
+            string_quotes: &['"', '\''],
+            char_literals_only: true,
+        }
+    }
+
+    pub fn javascript() -> Self {
+        Self {
+            keywords: JAVASCRIPT_KEYWORDS,
+            line_comment: "//",
+            block_comment: Some(("/*", "*/")),
+            string_quotes: &['"', '\'', '`'],
+            char_literals_only: false,
+        }
+    }
+
+    pub fn python() -> Self {
+        Self {
+            keywords: PYTHON_KEYWORDS,
+            line_comment: "#",
+            block_comment: None,
+            string_quotes: &['"', '\''],
+            char_literals_only: false,
+        }
+    }
+
+    fn starts_with(chars: &[char], at: usize, pattern: &str) -> bool {
+        pattern
+            .chars()
+            .enumerate()
+            .all(|(i, p)| chars.get(at + i) == Some(&p))
+    }
+
+    /// Highlights one line. `in_block` carries block-comment state between lines.
+    fn highlight_line(&self, line: &str, in_block: &mut bool) -> Vec<TokenKind> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut kinds = vec![TokenKind::Plain; chars.len()];
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+        let mut i = 0;
+
+        while i < chars.len() {
+            if *in_block {
+                let (_, close) = self.block_comment.unwrap_or(("", ""));
+                if Self::starts_with(&chars, i, close) {
+                    let end = (i + close.len()).min(chars.len());
+                    kinds[i..end].fill(TokenKind::Comment);
+                    i = end;
+                    *in_block = false;
+                } else {
+                    kinds[i] = TokenKind::Comment;
+                    i += 1;
+                }
+                continue;
+            }
+
+            let ch = chars[i];
+
+            if Self::starts_with(&chars, i, self.line_comment) {
+                kinds[i..].fill(TokenKind::Comment);
+                break;
+            }
+
+            if let Some((open, _)) = self.block_comment
+                && Self::starts_with(&chars, i, open)
+            {
+                let end = (i + open.len()).min(chars.len());
+                kinds[i..end].fill(TokenKind::Comment);
+                i = end;
+                *in_block = true;
+                continue;
+            }
+
+            if self.string_quotes.contains(&ch) {
+                if let Some(end) = self.string_end(&chars, i) {
+                    kinds[i..=end].fill(TokenKind::String);
+                    i = end + 1;
+                } else {
+                    kinds[i] = TokenKind::Punctuation;
+                    i += 1;
+                }
+                continue;
+            }
+
+            if ch.is_ascii_digit() {
+                let start = i;
+                while i < chars.len() && (is_ident(chars[i]) || chars[i] == '.') {
+                    i += 1;
+                }
+                kinds[start..i].fill(TokenKind::Number);
+                continue;
+            }
+
+            if is_ident(ch) {
+                let start = i;
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let kind = if self.keywords.contains(&word.as_str()) {
+                    TokenKind::Keyword
+                } else if ch.is_uppercase() {
+                    TokenKind::Type
+                } else {
+                    TokenKind::Plain
+                };
+                kinds[start..i].fill(kind);
+                continue;
+            }
+
+            if !ch.is_whitespace() {
+                kinds[i] = TokenKind::Punctuation;
+            }
+            i += 1;
+        }
+
+        kinds
+    }
+
+    /// Index of the quote closing the string that opens at `start`, if any.
+    fn string_end(&self, chars: &[char], start: usize) -> Option<usize> {
+        let quote = chars[start];
+        let mut i = start + 1;
+
+        while i < chars.len() {
+            match chars[i] {
+                '\\' => i += 2,
+                c if c == quote => break,
+                _ => i += 1,
+            }
+        }
+
+        if i >= chars.len() {
+            // Unterminated: colour the rest of the line, except for Rust lifetimes.
+            return (!(self.char_literals_only && quote == '\'')).then(|| chars.len() - 1);
+        }
+
+        if self.char_literals_only && quote == '\'' && i - start > 3 {
+            return None;
+        }
+
+        Some(i)
+    }
+}
+
+impl Highlighter for SimpleHighlighter {
+    fn highlight(&self, lines: &[String]) -> Vec<Vec<TokenKind>> {
+        let mut in_block = false;
+        lines
+            .iter()
+            .map(|line| self.highlight_line(line, &mut in_block))
+            .collect()
+    }
+}
+
+pub fn highlighter_for(language: Language) -> Box<dyn Highlighter> {
+    match language {
+        Language::PlainText => Box::new(PlainHighlighter),
+        Language::Rust => Box::new(SimpleHighlighter::rust()),
+        Language::JavaScript => Box::new(SimpleHighlighter::javascript()),
+        Language::Python => Box::new(SimpleHighlighter::python()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TokenKind::*;
+
+    fn kinds(highlighter: &dyn Highlighter, text: &str) -> Vec<Vec<TokenKind>> {
+        let lines: Vec<std::string::String> = text.lines().map(|l| l.to_string()).collect();
+        highlighter.highlight(&lines)
+    }
+
+    #[test]
+    fn test_rust_tokens() {
+        let line = &kinds(&SimpleHighlighter::rust(), "let x = \"hi\"; // note")[0];
+
+        assert_eq!(&line[0..3], &[Keyword; 3]);
+        assert_eq!(line[4], Plain);
+        assert_eq!(line[6], Punctuation);
+        assert_eq!(&line[8..12], &[String; 4]);
+        assert_eq!(&line[14..], &[Comment; 7]);
+    }
+
+    #[test]
+    fn test_rust_lifetimes_are_not_strings() {
+        let line = &kinds(&SimpleHighlighter::rust(), "fn f<'a>(c: char) -> char { 'x' }")[0];
+        assert_eq!(line[5], Punctuation);
+        assert_eq!(line[6], Plain);
+        assert_eq!(&line[28..31], &[String; 3]);
+    }
+
+    #[test]
+    fn test_block_comments_span_lines() {
+        let lines = kinds(&SimpleHighlighter::javascript(), "a /* one\ntwo */ b");
+        assert_eq!(lines[0][0], Plain);
+        assert_eq!(&lines[0][2..], &[Comment; 6]);
+        assert_eq!(&lines[1][..6], &[Comment; 6]);
+        assert_eq!(lines[1][7], Plain);
+    }
+
+    #[test]
+    fn test_python_and_numbers() {
+        let line = &kinds(&SimpleHighlighter::python(), "def f(): return 42 # done")[0];
+        assert_eq!(&line[0..3], &[Keyword; 3]);
+        assert_eq!(&line[16..18], &[Number; 2]);
+        assert_eq!(line[19], Comment);
+    }
+
+    #[test]
+    fn test_plain_text() {
+        let lines = kinds(&PlainHighlighter, "fn main() {}");
+        assert!(lines[0].iter().all(|&k| k == Plain));
+    }
+}
@@ -1,45 +1,266 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Layout, Position, Rect},
+    widgets::{Block, Borders, Paragraph, Wrap},
     Frame,
 };
 
+use crate::app::{App, AppMode};
+use crate::ui::components::{self, BufferView};
+use crate::vim::CursorPosition;
+
+/// Below this size the UI is replaced by a "terminal too small" message.
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 18;
+
+/// From this width on, instructions and buffer sit side by side.
+pub const WIDE_WIDTH: u16 = 120;
+
+/// Below this height the lesson explanation is collapsed.
+pub const SHORT_HEIGHT: u16 = 30;
+
+pub fn fits(width: u16, height: u16) -> bool {
+    width >= MIN_WIDTH && height >= MIN_HEIGHT
+}
+
 pub struct AppLayout {
     pub header: Rect,
     pub instruction: Rect,
     pub buffer: Rect,
     pub feedback: Rect,
     pub footer: Rect,
+    /// Too short for the explanation; instructions show only the task.
+    pub compact: bool,
 }
 
 impl AppLayout {
-    pub fn new(area: Rect) -> Self {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(2),
-                Constraint::Min(10),
-                Constraint::Length(12),
-                Constraint::Length(5),
-                Constraint::Length(2),
-            ])
-            .split(area);
+    /// Lays out `area`. The buffer pane is sized for `buffer_lines` lines
+    /// before the instructions get the rest, unless the screen is wide
+    /// enough to put them side by side.
+    pub fn new(area: Rect, buffer_lines: usize) -> Self {
+        let compact = area.height < SHORT_HEIGHT;
+        let feedback_height = if compact { 5 } else { 6 };
+
+        let [header, middle, feedback, footer] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(feedback_height),
+            Constraint::Length(2),
+        ])
+        .areas(area);
+
+        let [instruction, buffer] = if area.width >= WIDE_WIDTH {
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(middle)
+        } else {
+            // Buffer lines plus the borders and the status line.
+            let wanted = (buffer_lines as u16).saturating_add(3).max(5);
+            let instruction_min = if compact { 3 } else { 8 };
+            let buffer_height = wanted.min(middle.height.saturating_sub(instruction_min));
+
+            Layout::vertical([Constraint::Min(0), Constraint::Length(buffer_height)])
+                .areas(middle)
+        };
 
         Self {
-            header: chunks[0],
-            instruction: chunks[1],
-            buffer: chunks[2],
-            feedback: chunks[3],
-            footer: chunks[4],
+            header,
+            instruction,
+            buffer,
+            feedback,
+            footer,
+            compact,
+        }
+    }
+}
+
+/// What a mouse event at a screen cell points at.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hit {
+    /// The lesson menu, and the menu index of the lesson under the mouse.
+    Menu { entry: Option<usize> },
+    /// The buffer pane, the cell under the mouse and its current scroll.
+    Buffer {
+        cell: Option<CursorPosition>,
+        offset: usize,
+        max_offset: usize,
+    },
+    Instructions { max_scroll: u16 },
+}
+
+/// Finds what is drawn at a screen cell, using the same layout as `draw_ui`.
+pub fn hit_test(app: &App, column: u16, row: u16) -> Option<Hit> {
+    let (width, height) = app.terminal_size?;
+    if !fits(width, height) || app.help.is_some() {
+        return None;
+    }
+
+    let layout = AppLayout::new(Rect::new(0, 0, width, height), app.task_buffer.line_count());
+    let position = Position::new(column, row);
+
+    match app.mode {
+        AppMode::Menu => {
+            let main = layout.instruction.union(layout.buffer);
+            main.contains(position).then(|| Hit::Menu {
+                entry: components::menu_entry_at(main, app, column, row),
+            })
         }
+        AppMode::Lesson if layout.buffer.contains(position) => {
+            let view = BufferView::new(layout.buffer, app);
+            Some(Hit::Buffer {
+                cell: view.cell_at(app, column, row),
+                offset: view.offset,
+                max_offset: view.max_offset,
+            })
+        }
+        AppMode::Lesson if layout.instruction.contains(position) => Some(Hit::Instructions {
+            max_scroll: components::instruction_scroll_limit(layout.instruction, app, layout.compact),
+        }),
+        _ => None,
     }
 }
 
+fn render_too_small(frame: &mut Frame, area: Rect) {
+    let message = format!(
+        "Terminal too small: {}x{}\nVEX needs at least {}x{}.\n\nResize the window or press Ctrl+Q to quit.",
+        area.width, area.height, MIN_WIDTH, MIN_HEIGHT
+    );
+    let paragraph = Paragraph::new(message)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL));
+
+    frame.render_widget(paragraph, area);
+}
+
 pub fn draw_ui(frame: &mut Frame, app: &crate::app::App) {
-    let layout = AppLayout::new(frame.area());
+    let area = frame.area();
+    if !fits(area.width, area.height) {
+        render_too_small(frame, area);
+        return;
+    }
+
+    let layout = AppLayout::new(area, app.task_buffer.line_count());
+    // Screens without a buffer pane use the instruction and buffer areas together.
+    let main = layout.instruction.union(layout.buffer);
 
     crate::ui::components::render_header(frame, layout.header, app);
-    crate::ui::components::render_instruction(frame, layout.instruction, app);
-    crate::ui::components::render_buffer(frame, layout.buffer, app);
+    if app.mode == AppMode::Summary {
+        crate::ui::components::render_summary(frame, main, app);
+    } else if app.mode == AppMode::Stats {
+        crate::ui::components::render_stats(frame, main, app);
+    } else if app.mode == AppMode::Menu {
+        crate::ui::components::render_instruction(frame, main, app, false);
+    } else {
+        crate::ui::components::render_instruction(frame, layout.instruction, app, layout.compact);
+        crate::ui::components::render_buffer(frame, layout.buffer, app);
+    }
+    if app.help.is_some() {
+        crate::ui::components::render_help(frame, main, app);
+    }
     crate::ui::components::render_feedback(frame, layout.feedback, app);
     crate::ui::components::render_footer(frame, layout.footer, app);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_side_by_side_on_wide_screens() {
+        let layout = AppLayout::new(Rect::new(0, 0, 160, 50), 9);
+        assert_eq!(layout.instruction.y, layout.buffer.y);
+        assert!(layout.instruction.x < layout.buffer.x);
+        assert!(!layout.compact);
+    }
+
+    #[test]
+    fn test_buffer_fits_on_80x24() {
+        let layout = AppLayout::new(Rect::new(0, 0, 80, 24), 9);
+        assert!(layout.compact);
+        assert!(layout.buffer.y > layout.instruction.y);
+        assert_eq!(layout.buffer.height, 12);
+        assert_eq!(layout.instruction.height, 3);
+    }
+
+    #[test]
+    fn test_tall_screens_keep_the_explanation() {
+        let layout = AppLayout::new(Rect::new(0, 0, 100, 40), 3);
+        assert!(!layout.compact);
+        assert_eq!(layout.buffer.height, 6);
+        assert!(layout.instruction.height > 8);
+    }
+
+    #[test]
+    fn test_hit_test() {
+        let mut app = App::new();
+        app.handle_event(crate::ui::Event::Resize(80, 24));
+
+        // Row 3 is the first section heading, row 4 the first lesson.
+        assert_eq!(hit_test(&app, 5, 3), Some(Hit::Menu { entry: None }));
+        assert_eq!(hit_test(&app, 5, 4), Some(Hit::Menu { entry: Some(0) }));
+
+        app.handle_event(crate::ui::Event::Key(crate::vim::Key::char('7')));
+        let layout = AppLayout::new(Rect::new(0, 0, 80, 24), app.task_buffer.line_count());
+        // Past the border and the gutter (" 1 │ "), on the second character of line 1.
+        let hit = hit_test(&app, layout.buffer.x + 7, layout.buffer.y + 1);
+        assert!(matches!(hit, Some(Hit::Buffer { cell: Some(cell), .. }) if cell == CursorPosition::new(0, 1)));
+        // Clicks past the end of a line land on its last character.
+        let hit = hit_test(&app, layout.buffer.x + 60, layout.buffer.y + 4);
+        assert!(matches!(hit, Some(Hit::Buffer { cell: Some(cell), .. }) if cell == CursorPosition::new(3, 0)));
+    }
+
+    #[test]
+    fn test_hit_test_long_lines_and_wide_characters() {
+        use ratatui::{backend::TestBackend, Terminal};
+        use unicode_width::UnicodeWidthStr;
+
+        let mut app = App::new();
+        app.handle_event(crate::ui::Event::Resize(80, 24));
+        app.start_lesson(1);
+        let long: String = ('a'..='z').cycle().take(200).collect();
+        app.task_buffer = crate::vim::Buffer::new(&format!("日本語 text\n{}", long));
+
+        // Clicks past the border and the gutter (" 1 │ ") must land on the
+        // character drawn there.
+        let layout = AppLayout::new(Rect::new(0, 0, 80, 24), app.task_buffer.line_count());
+        let text_x = layout.buffer.x + 6;
+        let check = |app: &App, x: u16, line: u16, expected: CursorPosition| {
+            let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+            terminal.draw(|frame| draw_ui(frame, app)).unwrap();
+            let y = layout.buffer.y + 1 + line;
+            let hit = hit_test(app, x, y);
+            assert!(matches!(hit, Some(Hit::Buffer { cell: Some(cell), .. }) if cell == expected));
+
+            let text = app.task_buffer.get_line(expected.line).unwrap();
+            let expected_char = text.chars().nth(expected.column).unwrap().to_string();
+            // The cell clicked, or the wide character covering it.
+            let cells = terminal.backend().buffer();
+            let mut start = 0;
+            loop {
+                let width = cells[(start, y)].symbol().width().max(1) as u16;
+                if start + width > x {
+                    break;
+                }
+                start += width;
+            }
+            assert_eq!(cells[(start, y)].symbol(), expected_char);
+        };
+
+        // Both halves of 本 and the space after 語.
+        check(&app, text_x + 2, 0, CursorPosition::new(0, 1));
+        check(&app, text_x + 3, 0, CursorPosition::new(0, 1));
+        check(&app, text_x + 6, 0, CursorPosition::new(0, 3));
+
+        // With the cursor far along the long line, the view scrolls sideways
+        // and keeps it in the middle of the 73 columns left for text.
+        app.task_buffer.set_cursor(CursorPosition::new(1, 150));
+        check(&app, text_x, 1, CursorPosition::new(1, 150 - 36));
+        check(&app, text_x + 36, 1, CursorPosition::new(1, 150));
+    }
+
+    #[test]
+    fn test_minimum_size() {
+        assert!(fits(80, 24));
+        assert!(!fits(59, 24));
+        assert!(!fits(80, 17));
+    }
+}
@@ -1,6 +1,8 @@
 pub mod components;
 pub mod events;
+pub mod highlight;
 pub mod layout;
+pub mod theme;
 
 pub use components::render_ui;
 pub use events::{Event, EventHandler};
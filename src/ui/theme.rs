@@ -0,0 +1,331 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+
+use crate::ui::highlight::TokenKind;
+
+/// The built-in color schemes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// No colors at all; overlays are told apart by modifiers only.
+    NoColor,
+}
+
+impl std::str::FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dark" => Ok(ThemeName::Dark),
+            "light" => Ok(ThemeName::Light),
+            "high-contrast" | "highcontrast" => Ok(ThemeName::HighContrast),
+            "no-color" | "none" => Ok(ThemeName::NoColor),
+            _ => Err(format!(
+                "Unknown theme '{}': use dark, light, high-contrast or no-color",
+                s
+            )),
+        }
+    }
+}
+
+/// Every style the UI draws with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: ThemeName,
+    /// Header bar.
+    pub title: Style,
+    /// Section names in the menu.
+    pub heading: Style,
+    /// Session and lesson titles.
+    pub emphasis: Style,
+    /// The current task description.
+    pub task: Style,
+    /// Secondary text: footer, separators, lesson details.
+    pub muted: Style,
+    /// Labels next to values, e.g. "Input:".
+    pub label: Style,
+    pub value: Style,
+    pub selected: Style,
+    pub success: Style,
+    pub error: Style,
+    pub warning: Style,
+    pub hint: Style,
+    pub gutter: Style,
+    pub status_line: Style,
+    pub status_mode: Style,
+    pub cursor: Style,
+    pub target: Style,
+    /// The learner's guess in a "where is this?" quiz.
+    pub guess: Style,
+    pub solution: Style,
+    /// Trail cells by age: the last few moves, older moves, the rest.
+    pub trail: [Style; 3],
+    pub your_path: Style,
+    pub optimal_path: Style,
+    pub keyword: Style,
+    pub type_name: Style,
+    pub string: Style,
+    pub number: Style,
+    pub comment: Style,
+    pub punctuation: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub fn from_name(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Self::dark(),
+            ThemeName::Light => Self::light(),
+            ThemeName::HighContrast => Self::high_contrast(),
+            ThemeName::NoColor => Self::no_color(),
+        }
+    }
+
+    /// The theme to use given the user's choice and the environment. A
+    /// non-empty `NO_COLOR` variable always wins (see no-color.org).
+    pub fn resolve(name: ThemeName) -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Self::from_name(if no_color { ThemeName::NoColor } else { name })
+    }
+
+    pub fn dark() -> Self {
+        let bold = |color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+        let fg = |color| Style::default().fg(color);
+
+        Self {
+            name: ThemeName::Dark,
+            title: bold(Color::Cyan),
+            heading: bold(Color::Cyan),
+            emphasis: bold(Color::Yellow),
+            task: bold(Color::Green),
+            muted: fg(Color::DarkGray),
+            label: fg(Color::Gray),
+            value: bold(Color::White),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            success: bold(Color::Green),
+            error: bold(Color::Red),
+            warning: fg(Color::Yellow),
+            hint: fg(Color::Cyan),
+            gutter: fg(Color::DarkGray),
+            status_line: fg(Color::White).bg(Color::DarkGray),
+            status_mode: bold(Color::Black).bg(Color::Cyan),
+            cursor: bold(Color::Black).bg(Color::Green),
+            target: bold(Color::Black).bg(Color::Magenta),
+            guess: bold(Color::Black).bg(Color::Yellow),
+            solution: fg(Color::Magenta).add_modifier(Modifier::UNDERLINED),
+            trail: [
+                fg(Color::Black).bg(Color::Blue),
+                Style::default().bg(Color::DarkGray),
+                fg(Color::DarkGray),
+            ],
+            your_path: fg(Color::Blue),
+            optimal_path: fg(Color::Magenta),
+            keyword: fg(Color::Yellow),
+            type_name: fg(Color::LightBlue),
+            string: fg(Color::LightGreen),
+            number: fg(Color::Cyan),
+            comment: fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            punctuation: fg(Color::Gray),
+        }
+    }
+
+    /// For light terminal backgrounds: no yellow or white text.
+    pub fn light() -> Self {
+        let bold = |color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+        let fg = |color| Style::default().fg(color);
+
+        Self {
+            name: ThemeName::Light,
+            title: bold(Color::Blue),
+            heading: bold(Color::Blue),
+            emphasis: bold(Color::Magenta),
+            task: bold(Color::Green),
+            muted: fg(Color::DarkGray),
+            label: fg(Color::DarkGray),
+            value: bold(Color::Black),
+            selected: Style::default().add_modifier(Modifier::REVERSED),
+            success: bold(Color::Green),
+            error: bold(Color::Red),
+            warning: bold(Color::Magenta),
+            hint: fg(Color::Blue),
+            gutter: fg(Color::Gray),
+            status_line: fg(Color::Black).bg(Color::Gray),
+            status_mode: bold(Color::White).bg(Color::Blue),
+            cursor: bold(Color::White).bg(Color::Green),
+            target: bold(Color::White).bg(Color::Magenta),
+            guess: bold(Color::White).bg(Color::Blue),
+            solution: fg(Color::Magenta).add_modifier(Modifier::UNDERLINED),
+            trail: [
+                fg(Color::Black).bg(Color::LightBlue),
+                fg(Color::Black).bg(Color::Gray),
+                fg(Color::Gray),
+            ],
+            your_path: fg(Color::Blue),
+            optimal_path: fg(Color::Magenta),
+            keyword: bold(Color::Magenta),
+            type_name: fg(Color::Blue),
+            string: fg(Color::Green),
+            number: fg(Color::Red),
+            comment: fg(Color::Gray).add_modifier(Modifier::ITALIC),
+            punctuation: fg(Color::DarkGray),
+        }
+    }
+
+    /// Bright colors on black, with overlays that also differ by shape so
+    /// they never rely on hue alone.
+    pub fn high_contrast() -> Self {
+        let bold = |color| Style::default().fg(color).add_modifier(Modifier::BOLD);
+        let fg = |color| Style::default().fg(color);
+
+        Self {
+            name: ThemeName::HighContrast,
+            title: bold(Color::White),
+            heading: bold(Color::LightYellow).add_modifier(Modifier::UNDERLINED),
+            emphasis: bold(Color::White),
+            task: bold(Color::LightYellow),
+            muted: fg(Color::Gray),
+            label: fg(Color::White),
+            value: bold(Color::White),
+            selected: bold(Color::Black).bg(Color::White),
+            success: bold(Color::LightGreen),
+            error: bold(Color::LightRed).add_modifier(Modifier::UNDERLINED),
+            warning: bold(Color::LightYellow),
+            hint: bold(Color::LightCyan),
+            gutter: fg(Color::Gray),
+            status_line: bold(Color::Black).bg(Color::White),
+            status_mode: bold(Color::Black).bg(Color::LightYellow),
+            cursor: bold(Color::Black).bg(Color::White),
+            target: bold(Color::Black)
+                .bg(Color::LightYellow)
+                .add_modifier(Modifier::UNDERLINED),
+            guess: bold(Color::Black).bg(Color::LightGreen),
+            solution: bold(Color::LightYellow).add_modifier(Modifier::UNDERLINED),
+            trail: [
+                fg(Color::Black).bg(Color::LightCyan),
+                fg(Color::LightCyan).add_modifier(Modifier::UNDERLINED),
+                fg(Color::LightCyan),
+            ],
+            your_path: bold(Color::LightCyan),
+            optimal_path: bold(Color::LightYellow),
+            keyword: bold(Color::LightYellow),
+            type_name: fg(Color::LightCyan),
+            string: fg(Color::LightGreen),
+            number: fg(Color::LightMagenta),
+            comment: fg(Color::Gray).add_modifier(Modifier::ITALIC),
+            punctuation: fg(Color::White),
+        }
+    }
+
+    pub fn no_color() -> Self {
+        let modifier = |m| Style::default().add_modifier(m);
+
+        Self {
+            name: ThemeName::NoColor,
+            title: modifier(Modifier::BOLD),
+            heading: modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            emphasis: modifier(Modifier::BOLD),
+            task: modifier(Modifier::BOLD),
+            muted: modifier(Modifier::DIM),
+            label: Style::default(),
+            value: modifier(Modifier::BOLD),
+            selected: modifier(Modifier::REVERSED),
+            success: modifier(Modifier::BOLD),
+            error: modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            warning: Style::default(),
+            hint: modifier(Modifier::ITALIC),
+            gutter: modifier(Modifier::DIM),
+            status_line: modifier(Modifier::REVERSED),
+            status_mode: modifier(Modifier::REVERSED | Modifier::BOLD),
+            cursor: modifier(Modifier::REVERSED | Modifier::BOLD),
+            target: modifier(Modifier::UNDERLINED | Modifier::BOLD),
+            guess: modifier(Modifier::REVERSED | Modifier::UNDERLINED),
+            solution: modifier(Modifier::UNDERLINED),
+            trail: [
+                modifier(Modifier::REVERSED | Modifier::DIM),
+                modifier(Modifier::DIM | Modifier::UNDERLINED),
+                modifier(Modifier::DIM),
+            ],
+            your_path: modifier(Modifier::BOLD),
+            optimal_path: modifier(Modifier::BOLD),
+            keyword: modifier(Modifier::BOLD),
+            type_name: Style::default(),
+            string: Style::default(),
+            number: Style::default(),
+            comment: modifier(Modifier::ITALIC),
+            punctuation: Style::default(),
+        }
+    }
+
+    pub fn token(&self, kind: TokenKind) -> Style {
+        match kind {
+            TokenKind::Plain => Style::default(),
+            TokenKind::Keyword => self.keyword,
+            TokenKind::Type => self.type_name,
+            TokenKind::String => self.string,
+            TokenKind::Number => self.number,
+            TokenKind::Comment => self.comment,
+            TokenKind::Punctuation => self.punctuation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_color(style: Style) -> bool {
+        style.fg.is_some() || style.bg.is_some()
+    }
+
+    #[test]
+    fn test_theme_names_round_trip() {
+        for (text, name) in [
+            ("dark", ThemeName::Dark),
+            ("light", ThemeName::Light),
+            ("High-Contrast", ThemeName::HighContrast),
+            ("no-color", ThemeName::NoColor),
+        ] {
+            assert_eq!(text.parse::<ThemeName>(), Ok(name));
+            assert_eq!(Theme::from_name(name).name, name);
+        }
+        assert!("solarized".parse::<ThemeName>().is_err());
+    }
+
+    #[test]
+    fn test_no_color_theme_sets_no_colors() {
+        let theme = Theme::no_color();
+        let styles = [
+            theme.title,
+            theme.cursor,
+            theme.target,
+            theme.solution,
+            theme.success,
+            theme.error,
+            theme.keyword,
+            theme.comment,
+        ];
+        assert!(styles.iter().all(|&s| !has_color(s)));
+        assert!(theme.trail.iter().all(|&s| !has_color(s)));
+    }
+
+    #[test]
+    fn test_overlays_are_distinct_in_every_theme() {
+        for theme in [Theme::dark(), Theme::light(), Theme::high_contrast(), Theme::no_color()] {
+            let overlays = [theme.cursor, theme.target, theme.solution, theme.trail[0]];
+            for (i, a) in overlays.iter().enumerate() {
+                for b in &overlays[i + 1..] {
+                    assert_ne!(a, b, "{:?}", theme.name);
+                }
+            }
+        }
+    }
+}
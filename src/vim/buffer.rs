@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthChar;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+use super::Options;
+
+/// Tab stops every 8 screen columns, Vim's default `tabstop`.
+const TABSTOP: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CursorPosition {
     pub line: usize,
     pub column: usize,
@@ -16,6 +22,12 @@ impl CursorPosition {
 pub struct Buffer {
     lines: Vec<String>,
     cursor: CursorPosition,
+    /// The screen column `j` and `k` aim for, like Vim's `curswant`;
+    /// `None` follows the cursor.
+    #[serde(skip)]
+    desired_column: Option<usize>,
+    #[serde(skip)]
+    options: Options,
 }
 
 impl Buffer {
@@ -30,6 +42,8 @@ impl Buffer {
         Self {
             lines,
             cursor: CursorPosition::new(0, 0),
+            desired_column: None,
+            options: Options::default(),
         }
     }
 
@@ -43,6 +57,8 @@ impl Buffer {
         Self {
             lines,
             cursor: CursorPosition::new(0, 0),
+            desired_column: None,
+            options: Options::default(),
         }
     }
 
@@ -50,8 +66,34 @@ impl Buffer {
         self.cursor
     }
 
+    /// Moves the cursor, clamped to the last line and its last character
+    /// like Vim's `check_cursor`. `j` and `k` then aim for its screen column.
     pub fn set_cursor(&mut self, position: CursorPosition) {
-        self.cursor = position;
+        let line = position.line.min(self.lines.len().saturating_sub(1));
+        let len = self.get_line(line).map_or(0, |l| l.chars().count());
+        self.cursor = CursorPosition::new(line, position.column.min(len.saturating_sub(1)));
+        self.desired_column = None;
+    }
+
+    /// The screen column `j` and `k` aim for: the cursor's, unless a
+    /// vertical motion went through a shorter line or `$` asked for the
+    /// line end (`usize::MAX`).
+    pub fn desired_column(&self) -> usize {
+        self.desired_column
+            .unwrap_or_else(|| self.screen_column(self.cursor.line, self.cursor.column))
+    }
+
+    pub fn set_desired_column(&mut self, column: usize) {
+        self.desired_column = Some(column);
+    }
+
+    /// The options that motions on this buffer follow.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn set_options(&mut self, options: Options) {
+        self.options = options;
     }
 
     pub fn lines(&self) -> &[String] {
@@ -67,40 +109,98 @@ impl Buffer {
     }
 
     pub fn current_line(&self) -> &str {
-        &self.lines[self.cursor.line]
+        self.get_line(self.cursor.line).unwrap_or("")
     }
 
     pub fn current_line_len(&self) -> usize {
-        self.current_line().len()
+        self.current_line().chars().count()
+    }
+
+    /// Screen columns taken by `c` when it starts at screen column `at`.
+    fn char_width(c: char, at: usize) -> usize {
+        if c == '\t' {
+            TABSTOP - at % TABSTOP
+        } else {
+            c.width().unwrap_or(2)
+        }
+    }
+
+    /// The screen column Vim puts the cursor at on character `column` of
+    /// `line`: where the character starts, or where it ends for a tab.
+    pub fn screen_column(&self, line: usize, column: usize) -> usize {
+        let mut start = 0;
+        for (index, c) in self.get_line(line).unwrap_or("").chars().enumerate() {
+            let width = Self::char_width(c, start);
+            if index == column {
+                return if c == '\t' { start + width - 1 } else { start };
+            }
+            start += width;
+        }
+        start
     }
 
-    fn is_word_char(c: char) -> bool {
-        c.is_alphanumeric() || c == '_'
+    /// The character of `line` shown at screen column `screen_column`, or
+    /// the last one if the line is shorter.
+    pub fn column_at_screen(&self, line: usize, screen_column: usize) -> usize {
+        let mut start = 0;
+        let mut count = 0;
+        for (index, c) in self.get_line(line).unwrap_or("").chars().enumerate() {
+            start += Self::char_width(c, start);
+            if start > screen_column {
+                return index;
+            }
+            count = index + 1;
+        }
+        count.saturating_sub(1)
+    }
+
+    fn is_word_char(&self, c: char) -> bool {
+        self.options.iskeyword.contains(c)
     }
 
     fn is_whitespace(c: char) -> bool {
         c.is_whitespace()
     }
 
+    /// Vim's character class: 0 for blanks, 1 for punctuation, 2 for
+    /// keyword characters. Scripts written without spaces between words
+    /// get a class of their own, so a word ends where the script changes.
+    pub fn char_class(&self, c: char) -> u32 {
+        match u32::from(c) {
+            _ if Self::is_whitespace(c) => 0,
+            0x3040..=0x309f => 0x3040, // Hiragana
+            0x30a0..=0x30ff => 0x30a0, // Katakana
+            0x3300..=0x9fff | 0xf900..=0xfaff | 0x20000..=0x2fa1f => 0x4e00, // CJK ideographs
+            0xac00..=0xd7a3 => 0xac00, // Hangul syllables
+            _ if self.is_word_char(c) => 2,
+            _ => 1,
+        }
+    }
+
+    /// The column of the first non-blank character of a line, or of its
+    /// last character if it is all blanks.
+    pub fn first_non_blank(&self, line: usize) -> usize {
+        let chars: Vec<char> = self.get_line(line).unwrap_or("").chars().collect();
+        chars
+            .iter()
+            .position(|&c| !Self::is_whitespace(c))
+            .unwrap_or(chars.len().saturating_sub(1))
+    }
+
     pub fn find_next_word_start(&self) -> CursorPosition {
         let line = self.current_line();
         let mut col = self.cursor.column;
+        let chars: Vec<char> = line.chars().collect();
 
-        if col >= line.len() {
+        if col >= chars.len() {
             return self.find_next_line_first_word();
         }
 
-        let chars: Vec<char> = line.chars().collect();
-
         if col < chars.len() {
-            let current_char = chars[col];
+            let class = self.char_class(chars[col]);
 
-            if Self::is_word_char(current_char) {
-                while col < chars.len() && Self::is_word_char(chars[col]) {
-                    col += 1;
-                }
-            } else if !Self::is_whitespace(current_char) {
-                while col < chars.len() && !Self::is_whitespace(chars[col]) && !Self::is_word_char(chars[col]) {
+            if class != 0 {
+                while col < chars.len() && self.char_class(chars[col]) == class {
                     col += 1;
                 }
             }
@@ -123,6 +223,10 @@ impl Buffer {
             let line = &self.lines[line_idx];
             let chars: Vec<char> = line.chars().collect();
 
+            // An empty line counts as a word; a line of blanks does not.
+            if chars.is_empty() {
+                return CursorPosition::new(line_idx, 0);
+            }
             for (col, &ch) in chars.iter().enumerate() {
                 if !Self::is_whitespace(ch) {
                     return CursorPosition::new(line_idx, col);
@@ -133,7 +237,7 @@ impl Buffer {
 
         CursorPosition::new(
             self.lines.len().saturating_sub(1),
-            self.lines.last().map(|l| l.len().saturating_sub(1)).unwrap_or(0),
+            self.lines.last().map(|l| l.chars().count().saturating_sub(1)).unwrap_or(0),
         )
     }
 
@@ -156,19 +260,9 @@ impl Buffer {
             return self.find_prev_line_last_word();
         }
 
-        let current_is_word = Self::is_word_char(chars[col]);
+        let class = self.char_class(chars[col]);
 
-        while col > 0 {
-            let prev_char = chars[col - 1];
-            if current_is_word {
-                if !Self::is_word_char(prev_char) {
-                    break;
-                }
-            } else {
-                if Self::is_whitespace(prev_char) || Self::is_word_char(prev_char) {
-                    break;
-                }
-            }
+        while col > 0 && self.char_class(chars[col - 1]) == class {
             col -= 1;
         }
 
@@ -193,22 +287,14 @@ impl Buffer {
                 }
 
                 if !Self::is_whitespace(chars[col]) {
-                    let current_is_word = Self::is_word_char(chars[col]);
-                    while col > 0 {
-                        let prev_char = chars[col - 1];
-                        if current_is_word {
-                            if !Self::is_word_char(prev_char) {
-                                break;
-                            }
-                        } else {
-                            if Self::is_whitespace(prev_char) || Self::is_word_char(prev_char) {
-                                break;
-                            }
-                        }
+                    let class = self.char_class(chars[col]);
+                    while col > 0 && self.char_class(chars[col - 1]) == class {
                         col -= 1;
                     }
                     return CursorPosition::new(line_idx, col);
                 }
+            } else {
+                return CursorPosition::new(line_idx, 0);
             }
 
             if line_idx == 0 {
@@ -239,17 +325,11 @@ impl Buffer {
             return self.find_next_line_word_end();
         }
 
-        let current_is_word = Self::is_word_char(chars[col]);
+        let class = self.char_class(chars[col]);
 
         while col < chars.len() {
-            if current_is_word {
-                if !Self::is_word_char(chars[col]) {
-                    return CursorPosition::new(self.cursor.line, col - 1);
-                }
-            } else {
-                if Self::is_whitespace(chars[col]) || Self::is_word_char(chars[col]) {
-                    return CursorPosition::new(self.cursor.line, col - 1);
-                }
+            if self.char_class(chars[col]) != class {
+                return CursorPosition::new(self.cursor.line, col - 1);
             }
             col += 1;
         }
@@ -270,17 +350,11 @@ impl Buffer {
             }
 
             if col < chars.len() {
-                let current_is_word = Self::is_word_char(chars[col]);
+                let class = self.char_class(chars[col]);
 
                 while col < chars.len() {
-                    if current_is_word {
-                        if !Self::is_word_char(chars[col]) {
-                            return CursorPosition::new(line_idx, col - 1);
-                        }
-                    } else {
-                        if Self::is_whitespace(chars[col]) || Self::is_word_char(chars[col]) {
-                            return CursorPosition::new(line_idx, col - 1);
-                        }
+                    if self.char_class(chars[col]) != class {
+                        return CursorPosition::new(line_idx, col - 1);
                     }
                     col += 1;
                 }
@@ -293,7 +367,7 @@ impl Buffer {
 
         CursorPosition::new(
             self.lines.len().saturating_sub(1),
-            self.lines.last().map(|l| l.len().saturating_sub(1)).unwrap_or(0),
+            self.lines.last().map(|l| l.chars().count().saturating_sub(1)).unwrap_or(0),
         )
     }
 }
@@ -323,4 +397,22 @@ mod tests {
         let buffer = Buffer::new("Line 1\nLine 2\nLine 3");
         assert_eq!(buffer.line_count(), 3);
     }
+
+    #[test]
+    fn test_set_cursor_clamps() {
+        // Found by tests/motion_properties.rs: a cursor past the last line
+        // made current_line() index out of bounds.
+        let mut buffer = Buffer::new("");
+        buffer.set_cursor(CursorPosition::new(1, 0));
+        assert_eq!(buffer.cursor(), CursorPosition::new(0, 0));
+        assert_eq!(buffer.current_line(), "");
+        buffer.set_cursor(CursorPosition::new(0, 1));
+        assert_eq!(buffer.cursor(), CursorPosition::new(0, 0));
+
+        let mut buffer = Buffer::new("ab\nc");
+        buffer.set_cursor(CursorPosition::new(0, 5));
+        assert_eq!(buffer.cursor(), CursorPosition::new(0, 1));
+        buffer.set_cursor(CursorPosition::new(9, 9));
+        assert_eq!(buffer.cursor(), CursorPosition::new(1, 0));
+    }
 }
@@ -29,7 +29,21 @@ impl Command {
 }
 
 impl Motion {
-    pub fn to_key_str(&self) -> &'static str {
+    pub const ALL: [Motion; 11] = [
+        Motion::Left,
+        Motion::Down,
+        Motion::Up,
+        Motion::Right,
+        Motion::WordForward,
+        Motion::WordBackward,
+        Motion::WordEnd,
+        Motion::LineStart,
+        Motion::LineEnd,
+        Motion::FileStart,
+        Motion::FileEnd,
+    ];
+
+    pub fn to_key_str(self) -> &'static str {
         match self {
             Motion::Left => "h",
             Motion::Down => "j",
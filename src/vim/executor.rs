@@ -1,3 +1,5 @@
+use std::fmt;
+
 use super::{Buffer, Command, CursorPosition, Motion};
 
 #[derive(Debug)]
@@ -5,6 +7,14 @@ pub enum VimError {
     InvalidMotion(String),
 }
 
+impl fmt::Display for VimError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VimError::InvalidMotion(message) => write!(f, "{}", message),
+        }
+    }
+}
+
 pub struct Executor;
 
 impl Executor {
@@ -29,14 +39,20 @@ impl Executor {
                 let col = if line_len > 0 { line_len - 1 } else { 0 };
                 CursorPosition::new(buffer.cursor().line, col)
             }
-            Motion::FileStart => CursorPosition::new(0, 0),
-            Motion::FileEnd => {
-                let last_line = buffer.line_count().saturating_sub(1);
-                CursorPosition::new(last_line, 0)
-            }
+            Motion::FileStart => Self::jump_to_line(buffer, 0),
+            Motion::FileEnd => Self::jump_to_line(buffer, buffer.line_count().saturating_sub(1)),
         };
 
+        let desired_column = buffer.desired_column();
         buffer.set_cursor(new_position);
+        match motion {
+            Motion::Up | Motion::Down => buffer.set_desired_column(desired_column),
+            Motion::FileStart | Motion::FileEnd if !buffer.options().startofline => {
+                buffer.set_desired_column(desired_column)
+            }
+            Motion::LineEnd => buffer.set_desired_column(usize::MAX),
+            _ => {}
+        }
         Ok(())
     }
 
@@ -44,6 +60,10 @@ impl Executor {
         let cursor = buffer.cursor();
         if cursor.column > 0 {
             CursorPosition::new(cursor.line, cursor.column - 1)
+        } else if cursor.line > 0 && buffer.options().wraps('h') {
+            let line = cursor.line - 1;
+            let line_len = buffer.get_line(line).map(|l| l.chars().count()).unwrap_or(0);
+            CursorPosition::new(line, line_len.saturating_sub(1))
         } else {
             cursor
         }
@@ -53,26 +73,29 @@ impl Executor {
         let cursor = buffer.cursor();
         let line_len = buffer.current_line_len();
 
-        if line_len == 0 {
-            return cursor;
-        }
-
-        let max_col = line_len - 1;
-        if cursor.column < max_col {
+        if cursor.column + 1 < line_len {
             CursorPosition::new(cursor.line, cursor.column + 1)
+        } else if cursor.line + 1 < buffer.line_count() && buffer.options().wraps('l') {
+            CursorPosition::new(cursor.line + 1, 0)
         } else {
             cursor
         }
     }
 
+    /// `gg` and `G`: the first non-blank with `startofline`, else the same
+    /// column where the line is long enough.
+    fn jump_to_line(buffer: &Buffer, line: usize) -> CursorPosition {
+        if buffer.options().startofline {
+            return CursorPosition::new(line, buffer.first_non_blank(line));
+        }
+        CursorPosition::new(line, buffer.column_at_screen(line, buffer.desired_column()))
+    }
+
     fn move_up(buffer: &Buffer) -> CursorPosition {
         let cursor = buffer.cursor();
         if cursor.line > 0 {
             let new_line = cursor.line - 1;
-            let line_len = buffer.get_line(new_line).map(|l| l.len()).unwrap_or(0);
-
-            let max_col = if line_len > 0 { line_len - 1 } else { 0 };
-            let new_col = cursor.column.min(max_col);
+            let new_col = buffer.column_at_screen(new_line, buffer.desired_column());
 
             CursorPosition::new(new_line, new_col)
         } else {
@@ -82,12 +105,9 @@ impl Executor {
 
     fn move_down(buffer: &Buffer) -> CursorPosition {
         let cursor = buffer.cursor();
-        if cursor.line < buffer.line_count() - 1 {
+        if cursor.line + 1 < buffer.line_count() {
             let new_line = cursor.line + 1;
-            let line_len = buffer.get_line(new_line).map(|l| l.len()).unwrap_or(0);
-
-            let max_col = if line_len > 0 { line_len - 1 } else { 0 };
-            let new_col = cursor.column.min(max_col);
+            let new_col = buffer.column_at_screen(new_line, buffer.desired_column());
 
             CursorPosition::new(new_line, new_col)
         } else {
@@ -99,6 +119,7 @@ impl Executor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::vim::Options;
 
     #[test]
     fn test_basic_movement() {
@@ -120,6 +141,21 @@ mod tests {
         assert_eq!(buffer.cursor().column, 3);
     }
 
+    #[test]
+    fn test_multibyte_characters() {
+        let mut buffer = Buffer::new("a │ b\nxy");
+        buffer.set_cursor(CursorPosition::new(0, 4));
+
+        Executor::execute(&mut buffer, Command::Motion(Motion::Right)).unwrap();
+        assert_eq!(buffer.cursor().column, 4);
+
+        Executor::execute(&mut buffer, Command::Motion(Motion::WordBackward)).unwrap();
+        assert_eq!(buffer.cursor().column, 2);
+
+        Executor::execute(&mut buffer, Command::Motion(Motion::LineEnd)).unwrap();
+        assert_eq!(buffer.cursor().column, 4);
+    }
+
     #[test]
     fn test_word_movement() {
         let mut buffer = Buffer::new("The quick brown");
@@ -130,4 +166,75 @@ mod tests {
         Executor::execute(&mut buffer, Command::Motion(Motion::WordForward)).unwrap();
         assert_eq!(buffer.cursor().column, 10);
     }
+
+    /// Runs `motions` from `start` with the given `:set` arguments and
+    /// returns where the cursor ends, or the first error with that position.
+    fn run(text: &str, start: (usize, usize), set: &[&str], motions: &[Motion]) -> (usize, usize, Option<String>) {
+        let mut buffer = Buffer::new(text);
+        let mut options = Options::default();
+        options.set_all(set.iter().copied()).unwrap();
+        buffer.set_options(options);
+        buffer.set_cursor(CursorPosition::new(start.0, start.1));
+
+        let mut error = None;
+        for &motion in motions {
+            if let Err(e) = Executor::execute(&mut buffer, Command::Motion(motion)) {
+                error = Some(e.to_string());
+                break;
+            }
+        }
+        (buffer.cursor().line, buffer.cursor().column, error)
+    }
+
+    #[test]
+    fn test_desired_column() {
+        let text = "a long line\nab\nanother line\n\tx";
+        let down = Motion::Down;
+        assert_eq!(run(text, (0, 8), &[], &[down, down]), (2, 8, None));
+        assert_eq!(run(text, (0, 8), &[], &[down, Motion::Left, down]), (2, 0, None));
+        assert_eq!(run(text, (1, 0), &[], &[Motion::LineEnd, down]), (2, 11, None));
+        // A tab is aimed at by where it ends on screen.
+        assert_eq!(run(text, (2, 7), &[], &[down]), (3, 0, None));
+        assert_eq!(run(text, (2, 8), &[], &[down]), (3, 1, None));
+    }
+
+    #[test]
+    fn test_iskeyword() {
+        let text = "foo-bar baz\nx";
+        assert_eq!(run(text, (0, 0), &[], &[Motion::WordForward]), (0, 3, None));
+        assert_eq!(run(text, (0, 0), &["isk+=-"], &[Motion::WordForward]), (0, 8, None));
+        assert_eq!(run(text, (0, 0), &["isk+=-"], &[Motion::WordEnd]), (0, 6, None));
+        assert_eq!(run(text, (0, 9), &["isk+=-"], &[Motion::WordBackward, Motion::WordBackward]), (0, 0, None));
+        // Without letters, a run of letters is punctuation.
+        assert_eq!(run("ab12 cd", (0, 0), &["isk=48-57"], &[Motion::WordForward]), (0, 2, None));
+    }
+
+    #[test]
+    fn test_whichwrap() {
+        let text = "ab\n\ncd";
+        assert_eq!(run(text, (0, 1), &[], &[Motion::Right]), (0, 1, None));
+        assert_eq!(run(text, (0, 1), &["ww+=l"], &[Motion::Right]), (1, 0, None));
+        assert_eq!(run(text, (0, 1), &["ww+=l"], &[Motion::Right, Motion::Right]), (2, 0, None));
+        assert_eq!(run(text, (2, 1), &["ww+=l"], &[Motion::Right]), (2, 1, None));
+        assert_eq!(run(text, (2, 0), &["ww+=l"], &[Motion::Left]), (2, 0, None));
+        assert_eq!(run(text, (2, 0), &["ww=h"], &[Motion::Left, Motion::Left]), (0, 1, None));
+        assert_eq!(run(text, (0, 0), &["ww=h"], &[Motion::Left]), (0, 0, None));
+    }
+
+    #[test]
+    fn test_startofline() {
+        let text = "  fn main() {\n    run();\n   ";
+        assert_eq!(run(text, (1, 8), &[], &[Motion::FileStart]), (0, 2, None));
+        assert_eq!(run(text, (0, 9), &[], &[Motion::FileEnd]), (2, 2, None));
+        assert_eq!(run(text, (1, 8), &["nosol"], &[Motion::FileStart]), (0, 8, None));
+        assert_eq!(run(text, (0, 9), &["nosol"], &[Motion::FileEnd]), (2, 2, None));
+    }
+
+    #[test]
+    fn test_out_of_range_start() {
+        // Minimized from tests/motion_properties.rs: these used to panic or
+        // leave the cursor past the end of the line.
+        assert_eq!(run("", (1, 0), &[], &[Motion::WordForward]), (0, 0, None));
+        assert_eq!(run("", (0, 1), &[], &[Motion::Down]), (0, 0, None));
+    }
 }
@@ -0,0 +1,334 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Which key was pressed: a character or one of the named keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    Esc,
+    Enter,
+    Backspace,
+    Tab,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    F(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    /// Only recorded for named keys; a shifted character is its own char.
+    pub shift: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        ctrl: false,
+        alt: false,
+        shift: false,
+    };
+
+    pub fn is_empty(self) -> bool {
+        self == Self::NONE
+    }
+}
+
+/// One key press with its modifiers. Formats and parses as Vim key notation:
+/// `w`, `<C-d>`, `<Esc>`, `<CR>`, `<S-Up>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: Modifiers,
+}
+
+/// Vim's names for named keys, with the name used when formatting first.
+const NAMES: [(&str, KeyCode); 19] = [
+    ("Esc", KeyCode::Esc),
+    ("CR", KeyCode::Enter),
+    ("BS", KeyCode::Backspace),
+    ("Tab", KeyCode::Tab),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Insert", KeyCode::Insert),
+    ("Del", KeyCode::Delete),
+    ("Space", KeyCode::Char(' ')),
+    ("lt", KeyCode::Char('<')),
+    ("Enter", KeyCode::Enter),
+    ("Return", KeyCode::Enter),
+    ("Bslash", KeyCode::Char('\\')),
+];
+
+impl Key {
+    /// Builds a key the way Vim reads it: Shift is dropped from characters
+    /// (`<S-g>` is `G`), and Ctrl with a letter ignores its case (`<C-D>`
+    /// is `<C-d>`).
+    pub fn new(code: KeyCode, mut modifiers: Modifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(c) => {
+                let shifted = std::mem::take(&mut modifiers.shift) && !modifiers.ctrl && !modifiers.alt;
+                KeyCode::Char(match c {
+                    _ if modifiers.ctrl => c.to_ascii_lowercase(),
+                    _ if shifted => c.to_ascii_uppercase(),
+                    _ => c,
+                })
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn char(c: char) -> Self {
+        Self::new(KeyCode::Char(c), Modifiers::NONE)
+    }
+
+    pub fn ctrl(c: char) -> Self {
+        Self::new(
+            KeyCode::Char(c),
+            Modifiers {
+                ctrl: true,
+                ..Modifiers::NONE
+            },
+        )
+    }
+
+    pub fn named(code: KeyCode) -> Self {
+        Self::new(code, Modifiers::NONE)
+    }
+
+    /// The character typed, if this is a plain character without Ctrl or Alt.
+    pub fn as_char(&self) -> Option<char> {
+        match self.code {
+            KeyCode::Char(c) if !self.modifiers.ctrl && !self.modifiers.alt => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn is_arrow(&self) -> bool {
+        matches!(
+            self.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
+        )
+    }
+
+    /// Parses a sequence in Vim notation, such as `"wb<C-d>gg"`. A `<` that
+    /// does not start a known key name stands for itself.
+    pub fn parse_sequence(keys: &str) -> Vec<Key> {
+        let mut result = Vec::new();
+        let mut rest = keys;
+
+        while let Some(c) = rest.chars().next() {
+            if c == '<'
+                && let Some(end) = rest.find('>')
+                && let Ok(key) = rest[..=end].parse()
+            {
+                result.push(key);
+                rest = &rest[end + 1..];
+                continue;
+            }
+
+            result.push(Key::char(c));
+            rest = &rest[c.len_utf8()..];
+        }
+
+        result
+    }
+
+    /// Like [`Key::parse_sequence`], with `<Leader>` standing for `leader`.
+    pub fn parse_with_leader(keys: &str, leader: Key) -> Vec<Key> {
+        const LEADER: &str = "<leader>";
+        let mut result = Vec::new();
+        let mut rest = keys;
+
+        while let Some(start) = rest.to_ascii_lowercase().find(LEADER) {
+            result.extend(Key::parse_sequence(&rest[..start]));
+            result.push(leader);
+            rest = &rest[start + LEADER.len()..];
+        }
+        result.extend(Key::parse_sequence(rest));
+        result
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self.code {
+            KeyCode::Char(c) if self.modifiers.is_empty() && c != '<' && c != ' ' => {
+                return write!(f, "{}", c);
+            }
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char('<') => "lt".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("F{}", n),
+            code => NAMES
+                .iter()
+                .find(|(_, named)| *named == code)
+                .map_or(String::new(), |(name, _)| name.to_string()),
+        };
+
+        write!(f, "<")?;
+        if self.modifiers.shift {
+            write!(f, "S-")?;
+        }
+        if self.modifiers.ctrl {
+            write!(f, "C-")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "M-")?;
+        }
+        write!(f, "{}>", name)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    /// Parses one key: a single character or a `<...>` name with optional
+    /// `C-`, `M-`/`A-` and `S-` prefixes, case-insensitively like Vim.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::char(c));
+        }
+
+        let inner = s
+            .strip_prefix('<')
+            .and_then(|s| s.strip_suffix('>'))
+            .filter(|inner| !inner.is_empty())
+            .ok_or_else(|| format!("Unknown key: '{}'", s))?;
+
+        let mut modifiers = Modifiers::NONE;
+        let mut name = inner;
+        while name.len() > 2 && name.as_bytes()[1] == b'-' {
+            match name.as_bytes()[0].to_ascii_uppercase() {
+                b'C' => modifiers.ctrl = true,
+                b'M' | b'A' => modifiers.alt = true,
+                b'S' => modifiers.shift = true,
+                _ => break,
+            }
+            name = &name[2..];
+        }
+
+        let mut name_chars = name.chars();
+        let code = if let (Some(c), None) = (name_chars.next(), name_chars.next()) {
+            KeyCode::Char(c)
+        } else if let Some(n) = name.strip_prefix(['F', 'f']).and_then(|n| n.parse::<u8>().ok()) {
+            // Any function key a terminal reports, not just Vim's <F1>-<F37>,
+            // so every key VEX writes reads back.
+            KeyCode::F(n)
+        } else {
+            NAMES
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(name))
+                .map(|&(_, code)| code)
+                .ok_or_else(|| format!("Unknown key: '{}'", s))?
+        };
+
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vim_notation() {
+        assert_eq!(Key::char('w').to_string(), "w");
+        assert_eq!(Key::ctrl('d').to_string(), "<C-d>");
+        assert_eq!(Key::named(KeyCode::Esc).to_string(), "<Esc>");
+        assert_eq!(Key::named(KeyCode::Enter).to_string(), "<CR>");
+        assert_eq!(Key::named(KeyCode::F(1)).to_string(), "<F1>");
+        assert_eq!(Key::char('<').to_string(), "<lt>");
+        assert_eq!(Key::char(' ').to_string(), "<Space>");
+        let shift_up = Key::new(
+            KeyCode::Up,
+            Modifiers {
+                shift: true,
+                ..Modifiers::NONE
+            },
+        );
+        assert_eq!(shift_up.to_string(), "<S-Up>");
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        for notation in ["w", "<C-d>", "<Esc>", "<CR>", "<BS>", "<S-Up>", "<M-x>", "<F12>", "<lt>", "<Space>"] {
+            let key: Key = notation.parse().unwrap();
+            assert_eq!(key.to_string(), notation);
+        }
+
+        assert_eq!("<c-D>".parse::<Key>(), Ok(Key::ctrl('d')));
+        assert_eq!("<esc>".parse::<Key>(), Ok(Key::named(KeyCode::Esc)));
+        assert_eq!("<Enter>".parse::<Key>(), Ok(Key::named(KeyCode::Enter)));
+        assert_eq!("<S-g>".parse::<Key>(), Ok(Key::char('G')));
+        assert!("<Nope>".parse::<Key>().is_err());
+        assert!("ab".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn test_every_key_round_trips() {
+        let mut codes: Vec<KeyCode> = NAMES.iter().map(|&(_, code)| code).collect();
+        codes.extend(['w', 'D', '<', '>', '-', ' ', '\\', 'é'].map(KeyCode::Char));
+        codes.extend((0..=u8::MAX).map(KeyCode::F));
+
+        for code in codes {
+            for bits in 0..8 {
+                let modifiers = Modifiers {
+                    ctrl: bits & 1 != 0,
+                    alt: bits & 2 != 0,
+                    shift: bits & 4 != 0,
+                };
+                let key = Key::new(code, modifiers);
+                assert_eq!(key.to_string().parse::<Key>(), Ok(key), "{}", key);
+            }
+        }
+
+        // What a terminal reports for Ctrl+Shift+d and Shift+g.
+        let ctrl_shift = Modifiers {
+            ctrl: true,
+            shift: true,
+            ..Modifiers::NONE
+        };
+        assert_eq!(Key::new(KeyCode::Char('D'), ctrl_shift), Key::ctrl('d'));
+        let shift = Modifiers {
+            shift: true,
+            ..Modifiers::NONE
+        };
+        assert_eq!(Key::new(KeyCode::Char('G'), shift), Key::char('G'));
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        assert_eq!(
+            Key::parse_sequence("gg<C-d>w<Esc>"),
+            vec![
+                Key::char('g'),
+                Key::char('g'),
+                Key::ctrl('d'),
+                Key::char('w'),
+                Key::named(KeyCode::Esc),
+            ]
+        );
+        // An unknown name is typed literally, as Vim does.
+        assert_eq!(Key::parse_sequence("<x"), vec![Key::char('<'), Key::char('x')]);
+        assert_eq!(
+            Key::parse_with_leader("<Leader>w<leader>", Key::char(',')),
+            Key::parse_sequence(",w,")
+        );
+    }
+}
@@ -0,0 +1,448 @@
+//! Normal-mode key mappings, as defined with `:nmap` and `:nnoremap`.
+//!
+//! A [`Mapper`] sits in front of the command parser: typed keys go in, and
+//! the keys the mappings turn them into come out.
+
+use std::time::{Duration, Instant};
+
+use super::Key;
+
+/// Vim's `maxmapdepth`: how many mappings may expand for a single key.
+const MAX_DEPTH: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mapping {
+    pub lhs: Vec<Key>,
+    /// Empty for `<Nop>`.
+    pub rhs: Vec<Key>,
+    /// Whether `rhs` is mapped again (`nmap`) or not (`nnoremap`).
+    pub recursive: bool,
+}
+
+impl Mapping {
+    /// The mapping in `:nmap` form, e.g. `nnoremap H 0`.
+    pub fn to_command(&self) -> String {
+        let keys = |keys: &[Key]| -> String { keys.iter().map(|k| k.to_string()).collect() };
+        let rhs = if self.rhs.is_empty() {
+            "<Nop>".to_string()
+        } else {
+            keys(&self.rhs)
+        };
+        let command = if self.recursive { "nmap" } else { "nnoremap" };
+        format!("{} {} {}", command, keys(&self.lhs), rhs)
+    }
+}
+
+/// A mapping command from a `.vimrc` or the `:` command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapCommand {
+    Map(Mapping),
+    Unmap(Vec<Key>),
+    /// `let mapleader = ...`
+    Leader(Key),
+}
+
+/// Whether `word` is `full` or an abbreviation of it at least `min`
+/// characters long, like Ex command names.
+fn abbreviates(word: &str, full: &str, min: usize) -> bool {
+    word.len() >= min && full.starts_with(word)
+}
+
+impl MapCommand {
+    /// Parses one line. Lines that are not about normal-mode mappings (other
+    /// modes, options, comments, `<expr>` and `<Plug>` mappings) give
+    /// `Ok(None)`, so a whole `.vimrc` can be read line by line.
+    pub fn parse(line: &str, leader: Key) -> Result<Option<MapCommand>, String> {
+        let line = line.trim().trim_start_matches(':').trim_start();
+        let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim_start();
+
+        let recursive = if abbreviates(word, "nmap", 2) || word == "map" {
+            true
+        } else if abbreviates(word, "nnoremap", 2) || abbreviates(word, "noremap", 2) {
+            false
+        } else if abbreviates(word, "nunmap", 3) || abbreviates(word, "unmap", 3) {
+            return match rest.split_whitespace().next() {
+                Some(lhs) => Ok(Some(MapCommand::Unmap(Key::parse_with_leader(lhs, leader)))),
+                None => Err(format!("E471: Argument required: {}", word)),
+            };
+        } else if word == "let" {
+            return Ok(parse_leader(rest).map(MapCommand::Leader));
+        } else {
+            return Ok(None);
+        };
+
+        let mut rest = rest;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if let Some(flag) = ["<silent>", "<buffer>", "<nowait>", "<unique>", "<special>"]
+                .iter()
+                .find(|flag| lower.starts_with(*flag))
+            {
+                rest = rest[flag.len()..].trim_start();
+            } else if lower.starts_with("<expr>") || lower.starts_with("<script>") {
+                return Ok(None);
+            } else {
+                break;
+            }
+        }
+
+        let (lhs, rhs) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let rhs = rhs.trim();
+        if lhs.is_empty() || rhs.is_empty() {
+            // Listing mappings: nothing to define.
+            return Ok(None);
+        }
+        let lower = format!("{} {}", lhs, rhs).to_ascii_lowercase();
+        if lower.contains("<plug>") || lower.contains("<sid>") {
+            return Ok(None);
+        }
+
+        let rhs = if rhs.eq_ignore_ascii_case("<nop>") {
+            Vec::new()
+        } else {
+            Key::parse_with_leader(rhs, leader)
+        };
+        Ok(Some(MapCommand::Map(Mapping {
+            lhs: Key::parse_with_leader(lhs, leader),
+            rhs,
+            recursive,
+        })))
+    }
+}
+
+/// The key in `let mapleader = ","` or `let g:mapleader = "\<Space>"`.
+fn parse_leader(assignment: &str) -> Option<Key> {
+    let (name, value) = assignment.split_once('=')?;
+    if !matches!(name.trim(), "mapleader" | "g:mapleader") {
+        return None;
+    }
+    let value = value.trim();
+    let text = if let Some(text) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        text.to_string()
+    } else {
+        let text = value.strip_prefix('"')?.strip_suffix('"')?;
+        text.replace("\\<", "<").replace("\\\\", "\\")
+    };
+    match Key::parse_sequence(&text)[..] {
+        [key] => Some(key),
+        _ => None,
+    }
+}
+
+/// Applies mappings to typed keys, waiting while the keys typed so far
+/// could still become a longer mapping.
+#[derive(Debug, Clone)]
+pub struct Mapper {
+    mappings: Vec<Mapping>,
+    /// What `<leader>` stands for in mappings defined from now on.
+    pub leader: Key,
+    /// Vim's `timeoutlen`: how long to wait for the rest of a mapping.
+    pub timeout: Duration,
+    /// Keys not yet resolved, each with whether it may still be mapped.
+    pending: Vec<(Key, bool)>,
+    pending_since: Option<Instant>,
+}
+
+impl Mapper {
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1000);
+
+    pub fn new() -> Self {
+        Self {
+            mappings: Vec::new(),
+            leader: Key::char('\\'),
+            timeout: Self::DEFAULT_TIMEOUT,
+            pending: Vec::new(),
+            pending_since: None,
+        }
+    }
+
+    pub fn mappings(&self) -> &[Mapping] {
+        &self.mappings
+    }
+
+    /// Adds a mapping, replacing any with the same left-hand side.
+    pub fn map(&mut self, mapping: Mapping) {
+        self.unmap(&mapping.lhs);
+        self.mappings.push(mapping);
+    }
+
+    pub fn unmap(&mut self, lhs: &[Key]) -> bool {
+        let count = self.mappings.len();
+        self.mappings.retain(|mapping| mapping.lhs != lhs);
+        self.mappings.len() != count
+    }
+
+    pub fn apply(&mut self, command: MapCommand) -> Result<(), String> {
+        match command {
+            MapCommand::Map(mapping) => self.map(mapping),
+            MapCommand::Unmap(lhs) => {
+                if !self.unmap(&lhs) {
+                    let lhs: String = lhs.iter().map(|k| k.to_string()).collect();
+                    return Err(format!("E31: No such mapping: {}", lhs));
+                }
+            }
+            MapCommand::Leader(key) => self.leader = key,
+        }
+        Ok(())
+    }
+
+    /// Runs a mapping command line. Returns false if the line is not one.
+    pub fn run(&mut self, line: &str) -> Result<bool, String> {
+        match MapCommand::parse(line, self.leader)? {
+            Some(command) => self.apply(command).map(|_| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Reads every mapping command of a `.vimrc`, ignoring other lines.
+    /// Returns the number of mappings defined, or the first error with its
+    /// line number.
+    pub fn load_vimrc(&mut self, text: &str) -> Result<usize, String> {
+        let mut count = 0;
+        for (index, line) in text.lines().enumerate() {
+            let at_line = |e| format!("line {}: {}", index + 1, e);
+            let Some(command) = MapCommand::parse(line, self.leader).map_err(at_line)? else {
+                continue;
+            };
+            if matches!(command, MapCommand::Map(_)) {
+                count += 1;
+            }
+            self.apply(command).map_err(at_line)?;
+        }
+        Ok(count)
+    }
+
+    /// Keys typed but not yet resolved.
+    pub fn pending_keys(&self) -> Vec<Key> {
+        self.pending.iter().map(|&(key, _)| key).collect()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// When the pending keys stop waiting for a longer mapping.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.pending_since.map(|since| since + self.timeout)
+    }
+
+    /// Whether the pending keys have waited longer than the timeout.
+    pub fn timed_out(&self, now: Instant) -> bool {
+        self.deadline().is_some_and(|deadline| now >= deadline)
+    }
+
+    /// Takes a key typed at `now` and returns the keys that are now decided.
+    pub fn feed(&mut self, key: Key, now: Instant) -> Result<Vec<Key>, String> {
+        self.pending.push((key, true));
+        self.resolve(Some(now))
+    }
+
+    /// Gives up waiting for longer mappings, as after the timeout.
+    pub fn flush(&mut self) -> Result<Vec<Key>, String> {
+        self.resolve(None)
+    }
+
+    /// Drops the pending keys.
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.pending_since = None;
+    }
+
+    /// Maps what it can; keys that could still grow into a longer mapping
+    /// wait if `now` is given.
+    fn resolve(&mut self, now: Option<Instant>) -> Result<Vec<Key>, String> {
+        let mut output = Vec::new();
+        let mut depth = 0;
+
+        while let Some(&(first, remappable)) = self.pending.first() {
+            if !remappable {
+                output.push(first);
+                self.pending.remove(0);
+                continue;
+            }
+
+            // Only the leading run of keys that may be mapped can match.
+            let typed: Vec<Key> = self
+                .pending
+                .iter()
+                .take_while(|(_, remappable)| *remappable)
+                .map(|&(key, _)| key)
+                .collect();
+            let could_grow = typed.len() == self.pending.len()
+                && self
+                    .mappings
+                    .iter()
+                    .any(|m| m.lhs.len() > typed.len() && m.lhs.starts_with(&typed));
+            if could_grow && let Some(now) = now {
+                self.pending_since.get_or_insert(now);
+                return Ok(output);
+            }
+
+            let Some(mapping) = self
+                .mappings
+                .iter()
+                .filter(|m| typed.starts_with(&m.lhs))
+                .max_by_key(|m| m.lhs.len())
+                .cloned()
+            else {
+                output.push(first);
+                self.pending.remove(0);
+                continue;
+            };
+
+            depth += 1;
+            if depth > MAX_DEPTH {
+                self.clear();
+                return Err("E223: recursive mapping".to_string());
+            }
+
+            // As in Vim, a recursive right-hand side that starts with its own
+            // left-hand side does not map that first key again.
+            let starts_with_lhs = mapping.rhs.starts_with(&mapping.lhs);
+            let expansion = mapping.rhs.iter().enumerate().map(|(index, &key)| {
+                (key, mapping.recursive && !(starts_with_lhs && index == 0))
+            });
+            self.pending.splice(..mapping.lhs.len(), expansion);
+        }
+
+        self.pending_since = None;
+        Ok(output)
+    }
+}
+
+impl Default for Mapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(notation: &str) -> Vec<Key> {
+        Key::parse_sequence(notation)
+    }
+
+    fn feed_all(mapper: &mut Mapper, notation: &str) -> String {
+        let mut output = Vec::new();
+        for key in keys(notation) {
+            output.extend(mapper.feed(key, Instant::now()).unwrap());
+        }
+        output.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let leader = Key::char(',');
+        let parse = |line| MapCommand::parse(line, leader).unwrap();
+
+        assert_eq!(
+            parse("nnoremap H 0"),
+            Some(MapCommand::Map(Mapping {
+                lhs: keys("H"),
+                rhs: keys("0"),
+                recursive: false,
+            }))
+        );
+        let Some(MapCommand::Map(mapping)) = parse(":nmap <silent> <Leader>e  $ ") else {
+            panic!("not a mapping");
+        };
+        assert_eq!(mapping.lhs, keys(",e"));
+        assert_eq!(mapping.rhs, keys("$"));
+        assert!(mapping.recursive);
+        assert_eq!(mapping.to_command(), "nmap ,e $");
+
+        let Some(MapCommand::Map(nop)) = parse("nn Q <Nop>") else {
+            panic!("not a mapping");
+        };
+        assert!(nop.rhs.is_empty());
+        assert_eq!(parse("nun H"), Some(MapCommand::Unmap(keys("H"))));
+        assert_eq!(parse("let mapleader = \"\\<Space>\""), Some(MapCommand::Leader(Key::char(' '))));
+        assert_eq!(parse("let g:mapleader=','"), Some(MapCommand::Leader(leader)));
+
+        for ignored in [
+            "\" a comment",
+            "set ignorecase",
+            "inoremap jk <Esc>",
+            "nnoremap <expr> j v:count ? 'j' : 'gj'",
+            "nmap <leader>c <Plug>Commentary",
+            "nmap",
+            "let g:loaded_netrw = 1",
+        ] {
+            assert_eq!(parse(ignored), None, "{}", ignored);
+        }
+        assert!(MapCommand::parse("nunmap", leader).is_err());
+    }
+
+    #[test]
+    fn test_recursive_and_non_recursive() {
+        let mut mapper = Mapper::new();
+        mapper.run("nnoremap L $").unwrap();
+        mapper.run("nmap E L").unwrap();
+        mapper.run("nnoremap W L").unwrap();
+
+        assert_eq!(feed_all(&mut mapper, "L"), "$");
+        assert_eq!(feed_all(&mut mapper, "E"), "$");
+        assert_eq!(feed_all(&mut mapper, "W"), "L");
+        assert_eq!(feed_all(&mut mapper, "wE"), "w$");
+
+        // Swapping two keys needs noremap; nmap would loop.
+        mapper.run("nnoremap ; :").unwrap();
+        mapper.run("nnoremap : ;").unwrap();
+        assert_eq!(feed_all(&mut mapper, ";:"), ":;");
+
+        mapper.run("nmap a b").unwrap();
+        mapper.run("nmap b a").unwrap();
+        assert_eq!(mapper.feed(Key::char('a'), Instant::now()).unwrap_err(), "E223: recursive mapping");
+        assert!(!mapper.is_pending());
+
+        // A right-hand side that starts with its left-hand side.
+        mapper.run("nmap j jl").unwrap();
+        mapper.run("nnoremap l h").unwrap();
+        assert_eq!(feed_all(&mut mapper, "j"), "jh");
+    }
+
+    #[test]
+    fn test_multi_key_lhs_and_timeout() {
+        let mut mapper = Mapper::new();
+        mapper.run("nnoremap gw e").unwrap();
+        mapper.run("nnoremap <leader>l $").unwrap();
+        mapper.run("nnoremap <leader>ll 0").unwrap();
+
+        assert_eq!(feed_all(&mut mapper, "g"), "");
+        assert_eq!(mapper.pending_keys(), keys("g"));
+        assert_eq!(feed_all(&mut mapper, "w"), "e");
+        // No mapping starts with "gg": the first g goes through, and the
+        // second waits as it may start "gw".
+        assert_eq!(feed_all(&mut mapper, "gg"), "g");
+        assert_eq!(mapper.flush().unwrap(), keys("g"));
+        assert_eq!(feed_all(&mut mapper, "\\x"), "\\x");
+
+        // "\l" is complete but could become "\ll": wait for the timeout.
+        assert_eq!(feed_all(&mut mapper, "\\l"), "");
+        let deadline = mapper.deadline().unwrap();
+        assert!(!mapper.timed_out(deadline - Duration::from_millis(1)));
+        assert!(mapper.timed_out(deadline));
+        assert_eq!(mapper.flush().unwrap(), keys("$"));
+        assert_eq!(feed_all(&mut mapper, "\\ll"), "0");
+        assert_eq!(feed_all(&mut mapper, "\\lw"), "$w");
+    }
+
+    #[test]
+    fn test_load_vimrc() {
+        let mut mapper = Mapper::new();
+        let vimrc = "set number\n\
+                     let mapleader = \",\"\n\
+                     nnoremap <leader>h 0\n\
+                     \" swap ; and :\n\
+                     nnoremap ; :\n\
+                     inoremap jk <Esc>\n";
+        assert_eq!(mapper.load_vimrc(vimrc), Ok(2));
+        assert_eq!(mapper.leader, Key::char(','));
+        assert_eq!(mapper.mappings()[0].lhs, keys(",h"));
+
+        assert_eq!(mapper.load_vimrc("nunmap Z\n"), Err("line 1: E31: No such mapping: Z".to_string()));
+    }
+}
@@ -1,9 +1,15 @@
 pub mod buffer;
 pub mod command;
 pub mod executor;
+pub mod key;
+pub mod mapping;
+pub mod options;
 pub mod parser;
 
 pub use buffer::{Buffer, CursorPosition};
 pub use command::{Command, Motion};
 pub use executor::Executor;
+pub use key::{Key, KeyCode, Modifiers};
+pub use mapping::{Mapper, Mapping};
+pub use options::Options;
 pub use parser::CommandParser;
@@ -0,0 +1,469 @@
+//! The Vim options that change how motions behave, set with `:set`, the
+//! `[options]` table of the config file or a `.vimrc`.
+
+use std::str::FromStr;
+
+/// The characters that make up a word for `w`, `b` and `e`, from
+/// an `iskeyword` value such as `@,48-57,_,192-255`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keywords {
+    spec: String,
+    latin1: [bool; 256],
+}
+
+impl Keywords {
+    /// Vim's default: letters, digits, `_` and the accented Latin-1 letters.
+    pub const DEFAULT: &'static str = "@,48-57,_,192-255";
+
+    /// Whether `c` is a word character. Characters above 255 are not
+    /// covered by `iskeyword` and count as word characters when they are
+    /// alphanumeric.
+    pub fn contains(&self, c: char) -> bool {
+        match usize::try_from(u32::from(c)) {
+            Ok(n) if n < 256 => self.latin1[n],
+            _ => c.is_alphanumeric(),
+        }
+    }
+
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    /// Parses one comma-separated part: `@` (letters), a character, a
+    /// character code, or a range of either, optionally negated with `^`.
+    /// Returns the range and whether only letters in it are meant.
+    fn parse_part(part: &str) -> Option<(bool, u32, u32, bool)> {
+        let (exclude, body) = match part.strip_prefix('^') {
+            Some(rest) if !rest.is_empty() => (true, rest),
+            _ => (false, part),
+        };
+        if body == "@" {
+            return Some((exclude, 0, 255, true));
+        }
+
+        let (from, rest) = Self::parse_char(body)?;
+        let to = match rest.strip_prefix('-') {
+            Some(rest) if !rest.is_empty() => match Self::parse_char(rest)? {
+                (to, "") => to,
+                _ => return None,
+            },
+            _ if rest.is_empty() => from,
+            _ => return None,
+        };
+        (from <= to && to < 256).then_some((exclude, from, to, false))
+    }
+
+    fn parse_char(text: &str) -> Option<(u32, &str)> {
+        let digits = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+        if digits > 0 {
+            return Some((text[..digits].parse().ok()?, &text[digits..]));
+        }
+        let c = text.chars().next()?;
+        Some((u32::from(c), &text[c.len_utf8()..]))
+    }
+}
+
+impl FromStr for Keywords {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut latin1 = [false; 256];
+        if !s.is_empty() {
+            for part in s.split(',') {
+                let (exclude, from, to, letters) =
+                    Self::parse_part(part).ok_or_else(|| format!("invalid part {:?}", part))?;
+                for n in from..=to {
+                    let c = char::from_u32(n).unwrap_or_default();
+                    if !letters || c.is_lowercase() || c.is_uppercase() {
+                        latin1[n as usize] = !exclude;
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            spec: s.to_string(),
+            latin1,
+        })
+    }
+}
+
+impl Default for Keywords {
+    fn default() -> Self {
+        Self::DEFAULT.parse().expect("the default iskeyword is valid")
+    }
+}
+
+/// An option VEX knows, with its full and short names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Name {
+    IsKeyword,
+    WhichWrap,
+    StartOfLine,
+    WrapScan,
+    IgnoreCase,
+    SmartCase,
+    ShiftWidth,
+}
+
+impl Name {
+    const ALL: [Name; 7] = [
+        Name::IsKeyword,
+        Name::WhichWrap,
+        Name::StartOfLine,
+        Name::WrapScan,
+        Name::IgnoreCase,
+        Name::SmartCase,
+        Name::ShiftWidth,
+    ];
+
+    fn names(self) -> (&'static str, &'static str) {
+        match self {
+            Name::IsKeyword => ("iskeyword", "isk"),
+            Name::WhichWrap => ("whichwrap", "ww"),
+            Name::StartOfLine => ("startofline", "sol"),
+            Name::WrapScan => ("wrapscan", "ws"),
+            Name::IgnoreCase => ("ignorecase", "ic"),
+            Name::SmartCase => ("smartcase", "scs"),
+            Name::ShiftWidth => ("shiftwidth", "sw"),
+        }
+    }
+
+    fn lookup(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|n| {
+            let (long, short) = n.names();
+            name == long || name == short
+        })
+    }
+
+    fn is_bool(self) -> bool {
+        matches!(
+            self,
+            Name::StartOfLine | Name::WrapScan | Name::IgnoreCase | Name::SmartCase
+        )
+    }
+}
+
+/// Flags `whichwrap` accepts. VEX's motions only look at `h` and `l`.
+const WHICHWRAP_FLAGS: &[&str] = &["b", "s", "h", "l", "<", ">", "~", "[", "]"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// `iskeyword`: the characters of a word.
+    pub iskeyword: Keywords,
+    /// `whichwrap`: comma-separated keys that may move to the previous or
+    /// next line at the start or end of one.
+    pub whichwrap: String,
+    /// `startofline`: `gg` and `G` go to the first non-blank instead of
+    /// keeping the column.
+    pub startofline: bool,
+    /// `wrapscan`: only used by searches, which VEX does not teach; kept
+    /// so that `.vimrc` files and `:set` accept it.
+    pub wrapscan: bool,
+    /// `ignorecase`: only used by searches, like `wrapscan`.
+    pub ignorecase: bool,
+    /// `smartcase`: only applies to typed search patterns, which VEX does
+    /// not have; kept so that `.vimrc` files and `:set` accept it.
+    pub smartcase: bool,
+    /// `shiftwidth`: only used by `>>` and `<<`, which VEX does not teach.
+    pub shiftwidth: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            iskeyword: Keywords::default(),
+            whichwrap: "b,s".to_string(),
+            startofline: true,
+            wrapscan: true,
+            ignorecase: false,
+            smartcase: false,
+            shiftwidth: 8,
+        }
+    }
+}
+
+impl Options {
+    /// Whether `whichwrap` lets `key` (`h` or `l`) move to another line.
+    pub fn wraps(&self, key: char) -> bool {
+        self.whichwrap.split(',').any(|flag| flag.chars().eq([key]))
+    }
+
+    /// Runs one argument of `:set`: `name`, `noname`, `invname`, `name!`,
+    /// `name&`, `name?`, `name=value`, `name+=value`, `name-=value` or
+    /// `name^=value`. Returns the text Vim would show, if any.
+    pub fn set(&mut self, arg: &str) -> Result<Option<String>, String> {
+        let invalid = || format!("E474: Invalid argument: {}", arg);
+        let end = arg.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(arg.len());
+        let (word, rest) = arg.split_at(end);
+
+        let (name, prefix) = match Name::lookup(word) {
+            Some(name) => (name, ""),
+            None => ["no", "inv"]
+                .into_iter()
+                .find_map(|prefix| {
+                    let name = Name::lookup(word.strip_prefix(prefix)?)?;
+                    Some((name, prefix))
+                })
+                .ok_or_else(|| format!("E518: Unknown option: {}", word))?,
+        };
+        if !prefix.is_empty() && (!name.is_bool() || !rest.is_empty()) {
+            return Err(invalid());
+        }
+
+        match (rest, name.is_bool()) {
+            ("?", _) | ("", false) => return Ok(Some(self.show(name))),
+            ("&" | "&vim", _) => {
+                self.reset(name);
+                return Ok(None);
+            }
+            ("", true) | ("!", true) => {
+                let on = match prefix {
+                    "no" => false,
+                    "inv" => !self.flag(name),
+                    _ if rest == "!" => !self.flag(name),
+                    _ => true,
+                };
+                self.set_flag(name, on);
+                return Ok(None);
+            }
+            _ => {}
+        }
+        if name.is_bool() {
+            return Err(invalid());
+        }
+
+        let (operator, value) = ["+=", "-=", "^=", "=", ":"]
+            .iter()
+            .find_map(|op| rest.strip_prefix(op).map(|value| (*op, value)))
+            .ok_or_else(invalid)?;
+
+        if name == Name::ShiftWidth {
+            let number: usize = value
+                .parse()
+                .map_err(|_| format!("E521: Number required after =: {}", arg))?;
+            self.shiftwidth = match operator {
+                "+=" => self.shiftwidth.saturating_add(number),
+                "-=" => self
+                    .shiftwidth
+                    .checked_sub(number)
+                    .ok_or_else(|| format!("E487: Argument must be positive: {}", arg))?,
+                "^=" => self.shiftwidth.saturating_mul(number),
+                _ => number,
+            };
+            return Ok(None);
+        }
+
+        let current = match name {
+            Name::IsKeyword => self.iskeyword.spec().to_string(),
+            _ => self.whichwrap.clone(),
+        };
+        let list = match operator {
+            "+=" => append(&current, value),
+            "-=" => remove(&current, value),
+            "^=" => prepend(&current, value),
+            _ => value.to_string(),
+        };
+        match name {
+            Name::IsKeyword => self.iskeyword = list.parse().map_err(|_| invalid())?,
+            _ => {
+                if !list.is_empty() && !list.split(',').all(|flag| WHICHWRAP_FLAGS.contains(&flag)) {
+                    return Err(invalid());
+                }
+                self.whichwrap = list;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Runs every argument in turn, stopping at the first error. Returns
+    /// what the arguments showed, joined by two spaces.
+    pub fn set_all<'a>(&mut self, args: impl IntoIterator<Item = &'a str>) -> Result<String, String> {
+        let mut shown = Vec::new();
+        for arg in args {
+            shown.extend(self.set(arg)?);
+        }
+        Ok(shown.join("  "))
+    }
+
+    /// The options that differ from their defaults, as `:set` with no
+    /// arguments lists them.
+    pub fn changed(&self) -> Vec<String> {
+        let defaults = Self::default();
+        Name::ALL
+            .into_iter()
+            .filter(|&name| self.show(name) != defaults.show(name))
+            .map(|name| self.show(name))
+            .collect()
+    }
+
+    /// The arguments of a `:set` line such as `set ic sw=4 " comment`, or
+    /// `None` for any other line.
+    pub fn set_arguments(line: &str) -> Option<Vec<&str>> {
+        let line = line.trim().trim_start_matches(':');
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        if !matches!(command, "se" | "set" | "setl" | "setlocal" | "setg" | "setglobal") {
+            return None;
+        }
+        Some(rest.split_whitespace().take_while(|arg| !arg.starts_with('"')).collect())
+    }
+
+    /// Whether `arg` names an option VEX knows. Others are skipped when
+    /// reading a `.vimrc`.
+    pub fn is_known(arg: &str) -> bool {
+        let end = arg.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(arg.len());
+        let word = &arg[..end];
+        Name::lookup(word)
+            .or_else(|| word.strip_prefix("no").and_then(Name::lookup))
+            .or_else(|| word.strip_prefix("inv").and_then(Name::lookup))
+            .is_some()
+    }
+
+    /// Runs the known options of every `:set` line of a `.vimrc`, ignoring
+    /// other lines and options. Returns the number of options set, or the
+    /// first error with its line number.
+    pub fn load_vimrc(&mut self, text: &str) -> Result<usize, String> {
+        let mut count = 0;
+        for (index, line) in text.lines().enumerate() {
+            let Some(args) = Self::set_arguments(line) else {
+                continue;
+            };
+            for arg in args.into_iter().filter(|arg| Self::is_known(arg)) {
+                self.set(arg).map_err(|e| format!("line {}: {}", index + 1, e))?;
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    fn flag(&self, name: Name) -> bool {
+        match name {
+            Name::StartOfLine => self.startofline,
+            Name::WrapScan => self.wrapscan,
+            Name::IgnoreCase => self.ignorecase,
+            Name::SmartCase => self.smartcase,
+            _ => false,
+        }
+    }
+
+    fn set_flag(&mut self, name: Name, on: bool) {
+        match name {
+            Name::StartOfLine => self.startofline = on,
+            Name::WrapScan => self.wrapscan = on,
+            Name::IgnoreCase => self.ignorecase = on,
+            Name::SmartCase => self.smartcase = on,
+            _ => {}
+        }
+    }
+
+    fn reset(&mut self, name: Name) {
+        let defaults = Self::default();
+        match name {
+            Name::IsKeyword => self.iskeyword = defaults.iskeyword,
+            Name::WhichWrap => self.whichwrap = defaults.whichwrap,
+            Name::ShiftWidth => self.shiftwidth = defaults.shiftwidth,
+            flag => self.set_flag(flag, defaults.flag(flag)),
+        }
+    }
+
+    /// The option as `:set name?` shows it.
+    fn show(&self, name: Name) -> String {
+        let long = name.names().0;
+        match name {
+            Name::IsKeyword => format!("{}={}", long, self.iskeyword.spec()),
+            Name::WhichWrap => format!("{}={}", long, self.whichwrap),
+            Name::ShiftWidth => format!("{}={}", long, self.shiftwidth),
+            flag if self.flag(flag) => long.to_string(),
+            _ => format!("no{}", long),
+        }
+    }
+}
+
+fn append(list: &str, value: &str) -> String {
+    if list.split(',').any(|item| item == value) {
+        list.to_string()
+    } else if list.is_empty() {
+        value.to_string()
+    } else {
+        format!("{},{}", list, value)
+    }
+}
+
+fn prepend(list: &str, value: &str) -> String {
+    if list.split(',').any(|item| item == value) {
+        list.to_string()
+    } else if list.is_empty() {
+        value.to_string()
+    } else {
+        format!("{},{}", value, list)
+    }
+}
+
+fn remove(list: &str, value: &str) -> String {
+    list.split(',').filter(|item| *item != value).collect::<Vec<_>>().join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keywords() {
+        let keywords = Keywords::default();
+        assert!(keywords.contains('a') && keywords.contains('_') && keywords.contains('7'));
+        assert!(keywords.contains('é') && keywords.contains('ж'));
+        assert!(!keywords.contains('-') && !keywords.contains(' ') && !keywords.contains('│'));
+
+        let keywords: Keywords = "@,-,^a,35-36,@-@".parse().unwrap();
+        assert!(keywords.contains('-') && keywords.contains('#') && keywords.contains('@'));
+        assert!(!keywords.contains('a') && keywords.contains('b') && !keywords.contains('1'));
+
+        assert!("48-".parse::<Keywords>().is_err());
+        assert!("57-48".parse::<Keywords>().is_err());
+        assert!("300".parse::<Keywords>().is_err());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut options = Options::default();
+        options.set_all(["noic", "invic", "ws!", "nosol", "sw=4", "sw+=2"]).unwrap();
+        assert!(options.ignorecase && !options.wrapscan && !options.startofline);
+        assert_eq!(options.shiftwidth, 6);
+
+        options.set_all(["isk+=-", "isk-=_", "ww+=h", "ww^=l", "ww-=b"]).unwrap();
+        assert_eq!(options.iskeyword.spec(), "@,48-57,192-255,-");
+        assert_eq!(options.whichwrap, "l,s,h");
+        assert!(options.wraps('h') && options.wraps('l'));
+
+        assert_eq!(options.set_all(["ww?", "ic", "sw"]).unwrap(), "whichwrap=l,s,h  shiftwidth=6");
+        assert_eq!(options.set("ignorecase?").unwrap().unwrap(), "ignorecase");
+        options.set_all(["ww&", "sol&"]).unwrap();
+        assert_eq!(
+            options.changed(),
+            vec!["iskeyword=@,48-57,192-255,-", "nowrapscan", "ignorecase", "shiftwidth=6"]
+        );
+
+        assert_eq!(options.set("number").unwrap_err(), "E518: Unknown option: number");
+        assert_eq!(options.set("ic=1").unwrap_err(), "E474: Invalid argument: ic=1");
+        assert_eq!(options.set("nosw").unwrap_err(), "E474: Invalid argument: nosw");
+        assert_eq!(options.set("sw=x").unwrap_err(), "E521: Number required after =: sw=x");
+        assert!(options.set("sw-=10").unwrap_err().starts_with("E487"));
+        assert!(options.set("ww=h,x").is_err());
+        assert!(options.set("isk=48-").is_err());
+        assert_eq!(options.shiftwidth, 6);
+    }
+
+    #[test]
+    fn test_load_vimrc() {
+        let mut options = Options::default();
+        let count = options
+            .load_vimrc("set nocompatible\nset ic scs \" search\n:se ww+=h,l\nnnoremap H 0\n")
+            .unwrap();
+        assert_eq!(count, 3);
+        assert!(options.ignorecase && options.smartcase && options.wraps('l'));
+        assert_eq!(Options::set_arguments("setl sw=2"), Some(vec!["sw=2"]));
+        assert_eq!(Options::set_arguments("settings"), None);
+
+        let error = options.load_vimrc("\nset sw=two").unwrap_err();
+        assert_eq!(error, "line 2: E521: Number required after =: sw=two");
+    }
+}
@@ -1,9 +1,11 @@
-use super::{Command, Motion};
+use super::{Command, Key, KeyCode, Motion};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParseResult {
     Complete(Command),
     Incomplete,
+    /// `<Esc>` dropped the keys typed so far.
+    Cancelled,
     Invalid(String),
 }
 
@@ -15,24 +17,44 @@ enum ParserState {
 
 pub struct CommandParser {
     state: ParserState,
+    /// Keys of the command being typed, like Vim's `showcmd`.
+    pending: String,
 }
 
 impl CommandParser {
     pub fn new() -> Self {
         Self {
             state: ParserState::Initial,
+            pending: String::new(),
         }
     }
 
     pub fn reset(&mut self) {
         self.state = ParserState::Initial;
+        self.pending.clear();
     }
 
-    pub fn parse_keystroke(&mut self, key: char) -> ParseResult {
-        match self.state {
-            ParserState::Initial => self.parse_initial(key),
-            ParserState::GotG => self.parse_after_g(key),
+    pub fn parse_keystroke(&mut self, key: Key) -> ParseResult {
+        if key.code == KeyCode::Esc {
+            self.reset();
+            return ParseResult::Cancelled;
         }
+
+        let result = match (key.as_char(), &self.state) {
+            (Some(c), ParserState::Initial) => self.parse_initial(c),
+            (Some(c), ParserState::GotG) => self.parse_after_g(c),
+            (None, _) => {
+                self.state = ParserState::Initial;
+                ParseResult::Invalid(format!("Unknown key: {}", key))
+            }
+        };
+
+        if result == ParseResult::Incomplete {
+            self.pending.push_str(&key.to_string());
+        } else {
+            self.pending.clear();
+        }
+        result
     }
 
     fn parse_initial(&mut self, key: char) -> ParseResult {
@@ -67,6 +89,37 @@ impl CommandParser {
     pub fn is_incomplete(&self) -> bool {
         self.state != ParserState::Initial
     }
+
+    /// Keys typed so far for a command that is not complete yet.
+    pub fn pending_keys(&self) -> &str {
+        &self.pending
+    }
+
+    /// Parses a key string such as `"wbe$"` into the distinct motions it contains.
+    pub fn parse_motions(keys: &str) -> Result<Vec<Motion>, String> {
+        let mut parser = Self::new();
+        let mut motions = Vec::new();
+
+        for key in Key::parse_sequence(keys) {
+            match parser.parse_keystroke(key) {
+                ParseResult::Complete(command) => {
+                    if let Some(motion) = command.as_motion()
+                        && !motions.contains(&motion)
+                    {
+                        motions.push(motion);
+                    }
+                }
+                ParseResult::Incomplete | ParseResult::Cancelled => {}
+                ParseResult::Invalid(msg) => return Err(msg),
+            }
+        }
+
+        if parser.is_incomplete() {
+            return Err(format!("Incomplete command at end of '{}'", keys));
+        }
+
+        Ok(motions)
+    }
 }
 
 impl Default for CommandParser {
@@ -84,11 +137,11 @@ mod tests {
         let mut parser = CommandParser::new();
 
         assert_eq!(
-            parser.parse_keystroke('h'),
+            parser.parse_keystroke(Key::char('h')),
             ParseResult::Complete(Command::Motion(Motion::Left))
         );
         assert_eq!(
-            parser.parse_keystroke('j'),
+            parser.parse_keystroke(Key::char('j')),
             ParseResult::Complete(Command::Motion(Motion::Down))
         );
     }
@@ -97,19 +150,71 @@ mod tests {
     fn test_gg_command() {
         let mut parser = CommandParser::new();
 
-        assert_eq!(parser.parse_keystroke('g'), ParseResult::Incomplete);
+        assert_eq!(parser.parse_keystroke(Key::char('g')), ParseResult::Incomplete);
         assert_eq!(
-            parser.parse_keystroke('g'),
+            parser.parse_keystroke(Key::char('g')),
             ParseResult::Complete(Command::Motion(Motion::FileStart))
         );
     }
 
+    #[test]
+    fn test_pending_keys() {
+        let mut parser = CommandParser::new();
+
+        parser.parse_keystroke(Key::char('g'));
+        assert_eq!(parser.pending_keys(), "g");
+
+        parser.parse_keystroke(Key::char('g'));
+        assert_eq!(parser.pending_keys(), "");
+
+        parser.parse_keystroke(Key::char('g'));
+        parser.reset();
+        assert_eq!(parser.pending_keys(), "");
+    }
+
+    #[test]
+    fn test_parse_motions() {
+        assert_eq!(
+            CommandParser::parse_motions("wbgge w").map_err(|_| ()),
+            Err(())
+        );
+        assert_eq!(
+            CommandParser::parse_motions("wbggew").unwrap(),
+            vec![Motion::WordForward, Motion::WordBackward, Motion::FileStart, Motion::WordEnd]
+        );
+        assert!(CommandParser::parse_motions("wg").is_err());
+    }
+
+    #[test]
+    fn test_escape_cancels_pending() {
+        let mut parser = CommandParser::new();
+
+        parser.parse_keystroke(Key::char('g'));
+        assert_eq!(parser.parse_keystroke(Key::named(KeyCode::Esc)), ParseResult::Cancelled);
+        assert!(!parser.is_incomplete());
+        assert_eq!(parser.pending_keys(), "");
+        assert_eq!(
+            parser.parse_keystroke(Key::char('G')),
+            ParseResult::Complete(Command::Motion(Motion::FileEnd))
+        );
+    }
+
+    #[test]
+    fn test_ctrl_keys_are_not_characters() {
+        let mut parser = CommandParser::new();
+
+        assert_eq!(
+            parser.parse_keystroke(Key::ctrl('h')),
+            ParseResult::Invalid("Unknown key: <C-h>".to_string())
+        );
+    }
+
     #[test]
     fn test_invalid_after_g() {
         let mut parser = CommandParser::new();
 
-        assert_eq!(parser.parse_keystroke('g'), ParseResult::Incomplete);
-        match parser.parse_keystroke('x') {
+        assert_eq!(parser.parse_keystroke(Key::char('g')), ParseResult::Incomplete);
+        match parser.parse_keystroke(Key::char('x')) {
             ParseResult::Invalid(_) => (),
             _ => panic!("Expected invalid result"),
         }
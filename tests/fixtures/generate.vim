@@ -0,0 +1,98 @@
+" Records how real Vim moves the cursor, for tests/vim_fixtures.rs.
+" Run from the repository root; needs Vim 8.2.2324 or later:
+"
+"   vim -u NONE -N -i NONE -es -S tests/fixtures/generate.vim
+"
+" It writes tests/fixtures/motions.txt: every key sequence below, from every
+" position of every buffer, with the cursor Vim ends up at. Each command
+" runs in its own :normal, so a motion that fails does not cancel the rest,
+" as in VEX.
+
+set encoding=utf-8
+scriptencoding utf-8
+
+let s:motions = ['h', 'j', 'k', 'l', 'w', 'b', 'e', '0', '$', 'gg', 'G']
+let s:sequences = [
+      \ ['w', 'w'], ['w', 'w', 'w'], ['b', 'b'], ['e', 'e'], ['w', 'b'],
+      \ ['e', 'b'], ['b', 'e'], ['$', 'j'], ['$', 'k'], ['j', 'j'], ['k', 'k'],
+      \ ['l', 'j', 'k'],
+      \ ]
+let s:all = map(copy(s:motions), '[v:val]') + s:sequences
+
+" [buffer lines, options for :set, key sequences]
+let s:blocks = [
+      \ [['foo.bar baz'], '', s:all],
+      \ [['let value = compute(x);'], '', s:all],
+      \ [['a', '', 'b'], '', s:all],
+      \ [['foo', '', '', 'bar'], '', s:all],
+      \ [['  indented line', '    deeper', ''], '', s:all],
+      \ [['word   ', '   ', 'next'], '', s:all],
+      \ [['x'], '', s:all],
+      \ [[''], '', s:all],
+      \ [['if (a && b) {', '    return -1;', '}'], '', s:all],
+      \ [['fn main() {', '    let x = 1;', '}'], '', s:all],
+      \ [['foo--bar', '==>', '...baz'], '', s:all],
+      \ [['a_b c1 2d', 'CamelCase snake_case'], '', s:all],
+      \ [['one two', 'three four five', 'six'], '', s:all],
+      \ [['short', 'a much longer line here', 'mid length', 'x'], '', s:all],
+      \ [['end.', '.start'], '', s:all],
+      \ [['café naïve', 'über ß'], '', s:all],
+      \ [['a │ b', 'xy'], '', s:all],
+      \ [['日本語 text', 'abcdefgh', 'x日y'], '', s:all],
+      \ [['foo Foo FOO foo', 'bar foo'], '', s:all],
+      \ [["\tindented", "a\tb"], '', s:all],
+      \ [['last word'], '', s:all],
+      \ [['x = [1, 2, 3];', 'y = {a: b}'], '', s:all],
+      \ [['  ', '  x  ', ''], '', s:all],
+      \ [['foo--bar baz', 'x-y'], 'iskeyword+=-', [['w'], ['b'], ['e'], ['w', 'w']]],
+      \ [['foo.bar baz'], 'iskeyword=@,48-57,_,192-255,.', [['w'], ['b'], ['e']]],
+      \ [['a', '', 'bc', '   '], 'whichwrap+=h,l', [['h'], ['l'], ['h', 'h'], ['l', 'l']]],
+      \ [['fn main() {', '    let x = 1;', '}'], 'nostartofline', [['gg'], ['G'], ['$', 'gg'], ['j', 'G']]],
+      \ [['  fn main() {', '    let x = 1;', '   '], 'startofline', [['gg'], ['G']]],
+      \ ]
+
+let s:out = [
+      \ '# Motion fixtures recorded from Vim ' . v:version / 100 . '.' . v:version % 100
+      \   . ' by tests/fixtures/generate.vim. Do not edit by hand.',
+      \ '#',
+      \ '# buffer <JSON list of lines>   starts a block; the cases below it use this buffer',
+      \ '# set <options>                 :set arguments for the block, if any',
+      \ '# <line>,<col> <keys> <line>,<col>   start, keys typed, where Vim left the cursor',
+      \ '#',
+      \ '# Lines and columns count from 0, columns in characters. Motions never',
+      \ '# change the text, which the generator and the test runner both check.',
+      \ ]
+
+for [s:lines, s:options, s:keys] in s:blocks
+  silent %delete _
+  call setline(1, s:lines)
+  set iskeyword& whichwrap& startofline&
+  if s:options != ''
+    execute 'set ' . s:options
+  endif
+
+  call add(s:out, '')
+  call add(s:out, 'buffer ' . json_encode(s:lines))
+  if s:options != ''
+    call add(s:out, 'set ' . s:options)
+  endif
+
+  for s:keys_typed in s:keys
+    for s:line in range(len(s:lines))
+      for s:col in range(max([1, strchars(s:lines[s:line])]))
+        call setcursorcharpos(s:line + 1, s:col + 1)
+        for s:key in s:keys_typed
+          silent! execute 'normal! ' . s:key
+        endfor
+        if getline(1, '$') != s:lines
+          throw 'text changed by ' . join(s:keys_typed, '')
+        endif
+        call add(s:out, printf('%d,%d %s %d,%d', s:line, s:col, join(s:keys_typed, ''),
+              \ line('.') - 1, charcol('.') - 1))
+      endfor
+    endfor
+  endfor
+endfor
+
+call writefile(s:out, 'tests/fixtures/motions.txt')
+qall!
@@ -0,0 +1,143 @@
+//! Property tests for the motion engine: random key streams over random
+//! buffers, checked for panics, cursors out of bounds and motion pairs that
+//! should undo each other. Failures that proptest finds are kept, minimized,
+//! as unit tests next to the code they exposed.
+
+use proptest::prelude::*;
+use proptest::test_runner::Config;
+
+use vex::vim::parser::ParseResult;
+use vex::vim::{Buffer, Command, CommandParser, CursorPosition, Executor, Key, Motion, Options};
+
+/// Characters of every class the engine tells apart: blanks, keyword
+/// characters, punctuation, Latin-1 letters, wide CJK and kana, and symbols.
+/// Spaces come twice so that lines hold several short words.
+const CHARS: &[char] = &[
+    ' ', ' ', '\t', 'a', 'b', 'Z', '_', '1', '.', '-', '(', ')', 'é', '日', '本', 'ア', '│', '😀',
+];
+
+const KEYS: &[&str] = &[
+    "h", "j", "k", "l", "w", "b", "e", "0", "$", "g", "G", "x", "<Esc>",
+];
+
+const SETTINGS: &[&str] = &["isk+=-", "isk=", "isk=@", "isk=48-57", "ww=h,l", "nosol", "nows", "ic"];
+
+fn line() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(CHARS), 0..12).prop_map(|chars| chars.into_iter().collect())
+}
+
+fn buffer() -> impl Strategy<Value = Buffer> {
+    let options = prop::collection::vec(prop::sample::select(SETTINGS), 0..3);
+    (prop::collection::vec(line(), 0..6), options).prop_map(|(lines, set)| {
+        let mut buffer = Buffer::from_lines(lines);
+        let mut options = Options::default();
+        options.set_all(set).unwrap();
+        buffer.set_options(options);
+        buffer
+    })
+}
+
+fn keys() -> impl Strategy<Value = String> {
+    prop::collection::vec(prop::sample::select(KEYS), 0..30).prop_map(|keys| keys.concat())
+}
+
+/// A buffer with the cursor somewhere in it.
+fn placed_buffer() -> impl Strategy<Value = Buffer> {
+    buffer().prop_flat_map(|buffer| {
+        let lines = buffer.line_count();
+        (Just(buffer), 0..lines).prop_flat_map(|(buffer, line)| {
+            let len = buffer.get_line(line).map_or(0, |l| l.chars().count()).max(1);
+            (Just(buffer), Just(line), 0..len).prop_map(|(mut buffer, line, column)| {
+                buffer.set_cursor(CursorPosition::new(line, column));
+                buffer
+            })
+        })
+    })
+}
+
+fn in_bounds(buffer: &Buffer) -> bool {
+    let cursor = buffer.cursor();
+    let len = buffer.get_line(cursor.line).map_or(0, |l| l.chars().count());
+    cursor.line < buffer.line_count() && cursor.column < len.max(1)
+}
+
+fn motion(buffer: &Buffer, motion: Motion) -> Buffer {
+    let mut buffer = buffer.clone();
+    let _ = Executor::execute(&mut buffer, Command::Motion(motion));
+    buffer
+}
+
+/// Whether a word starts at the cursor, so `w` and `b` can stop there.
+fn at_word_start(buffer: &Buffer) -> bool {
+    let column = buffer.cursor().column;
+    let chars: Vec<char> = buffer.current_line().chars().collect();
+    let class = |i: usize| buffer.char_class(chars[i]);
+    chars.is_empty() || (class(column) != 0 && (column == 0 || class(column - 1) != class(column)))
+}
+
+proptest! {
+    #![proptest_config(Config { failure_persistence: None, ..Config::default() })]
+
+    #[test]
+    fn keys_never_leave_the_buffer(mut buffer in placed_buffer(), keys in keys()) {
+        let mut parser = CommandParser::new();
+        for key in Key::parse_sequence(&keys) {
+            if let ParseResult::Complete(command) = parser.parse_keystroke(key) {
+                let _ = Executor::execute(&mut buffer, command);
+                prop_assert!(in_bounds(&buffer), "{:?} after {:?}", buffer.cursor(), command);
+            }
+        }
+    }
+
+    #[test]
+    fn any_start_is_clamped(buffer in buffer(), line in 0usize..10, column in 0usize..20, keys in keys()) {
+        let mut buffer = buffer;
+        buffer.set_cursor(CursorPosition::new(line, column));
+        prop_assert!(in_bounds(&buffer));
+        let mut parser = CommandParser::new();
+        for key in Key::parse_sequence(&keys) {
+            if let ParseResult::Complete(command) = parser.parse_keystroke(key) {
+                let _ = Executor::execute(&mut buffer, command);
+                prop_assert!(in_bounds(&buffer));
+            }
+        }
+    }
+
+    #[test]
+    fn w_and_b_undo_each_other(buffer in placed_buffer()) {
+        if !at_word_start(&buffer) {
+            return Ok(());
+        }
+        let start = buffer.cursor();
+
+        let forward = motion(&buffer, Motion::WordForward);
+        if forward.cursor() != start && at_word_start(&forward) {
+            prop_assert_eq!(motion(&forward, Motion::WordBackward).cursor(), start);
+        }
+        let backward = motion(&buffer, Motion::WordBackward);
+        if backward.cursor() != start && at_word_start(&backward) {
+            prop_assert_eq!(motion(&backward, Motion::WordForward).cursor(), start);
+        }
+    }
+
+    #[test]
+    fn j_and_k_undo_each_other(buffer in placed_buffer()) {
+        let start = buffer.cursor();
+        if start.line + 1 < buffer.line_count() {
+            let down = motion(&buffer, Motion::Down);
+            prop_assert_eq!(motion(&down, Motion::Up).cursor(), start);
+        }
+        if start.line > 0 {
+            let up = motion(&buffer, Motion::Up);
+            prop_assert_eq!(motion(&up, Motion::Down).cursor(), start);
+        }
+    }
+
+    #[test]
+    fn jumps_are_idempotent(buffer in placed_buffer()) {
+        for jump in [Motion::FileStart, Motion::FileEnd, Motion::LineStart, Motion::LineEnd] {
+            let once = motion(&buffer, jump);
+            prop_assert_eq!(motion(&once, jump).cursor(), once.cursor(), "{:?}", jump);
+        }
+    }
+}
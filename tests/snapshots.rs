@@ -0,0 +1,190 @@
+//! Rendered-screen snapshots. Each test puts an `App` in some state, draws
+//! it with ratatui's `TestBackend` and compares the screen with a stored
+//! snapshot in `tests/snapshots/`.
+//!
+//! After an intentional UI change, regenerate the snapshots with
+//! `UPDATE_SNAPSHOTS=1 cargo test --test snapshots` and review the diff.
+
+use std::fs;
+use std::path::PathBuf;
+
+use ratatui::{
+    backend::TestBackend,
+    style::{Color, Modifier},
+    Terminal,
+};
+use vex::{App, Event};
+
+/// Sizes covered by the layout tests: the minimum, a classic terminal and a
+/// wide one that puts instructions and buffer side by side.
+const SIZES: [(u16, u16); 3] = [(60, 18), (80, 24), (120, 40)];
+
+/// A fresh app that believes it runs in a `width` x `height` terminal.
+fn app(width: u16, height: u16) -> App {
+    let mut app = App::new();
+    app.handle_event(Event::Resize(width, height));
+    app
+}
+
+/// The screen as text, followed by a mask of highlighted cells (reversed or
+/// with a background color), so that cursor and target blocks are covered
+/// even where they are drawn on a space.
+fn render(app: &App, width: u16, height: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| vex::ui::render_ui(frame, app)).unwrap();
+    let buffer = terminal.backend().buffer();
+
+    let mut text = String::new();
+    let mut highlights = String::new();
+    for y in 0..height {
+        let mut row = String::new();
+        let mut mask = String::new();
+        for x in 0..width {
+            let cell = &buffer[(x, y)];
+            row.push_str(cell.symbol());
+            let highlighted = cell.bg != Color::Reset
+                || cell.modifier.contains(Modifier::REVERSED);
+            mask.push(if highlighted { '#' } else { ' ' });
+        }
+        text.push_str(row.trim_end());
+        text.push('\n');
+        if mask.contains('#') {
+            highlights.push_str(&format!("{:>3} |{}\n", y, mask.trim_end()));
+        }
+    }
+
+    format!("{}x{}\n{}--- highlights ---\n{}", width, height, text, highlights)
+}
+
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing snapshot {}. Run `UPDATE_SNAPSHOTS=1 cargo test --test snapshots` to create it.",
+            path.display()
+        )
+    });
+
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(e, a)| e != a)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "Snapshot {} differs from line {}.\n--- expected\n{}\n--- actual\n{}\n\
+             Run `UPDATE_SNAPSHOTS=1 cargo test --test snapshots` if the change is intended.",
+            name,
+            line + 1,
+            expected,
+            actual
+        );
+    }
+}
+
+#[test]
+fn menu() {
+    for (width, height) in SIZES {
+        let app = app(width, height);
+        assert_snapshot(&format!("menu_{}x{}", width, height), &render(&app, width, height));
+    }
+}
+
+#[test]
+fn menu_with_selection_moved() {
+    let mut app = app(80, 24);
+    app.send_keys("jjj");
+    assert_snapshot("menu_selection", &render(&app, 80, 24));
+}
+
+#[test]
+fn terminal_too_small() {
+    let app = app(50, 15);
+    assert_snapshot("too_small", &render(&app, 50, 15));
+}
+
+#[test]
+fn first_task_of_each_lesson() {
+    let lesson_ids: Vec<usize> = App::new().curriculum.lessons().iter().map(|l| l.id).collect();
+
+    for id in lesson_ids {
+        let mut app = app(80, 24);
+        app.send_keys(&id.to_string());
+        assert_snapshot(&format!("lesson_{}_first_task", id), &render(&app, 80, 24));
+    }
+}
+
+#[test]
+fn lesson_at_each_size() {
+    for (width, height) in SIZES {
+        let mut app = app(width, height);
+        app.send_keys("2");
+        assert_snapshot(&format!("lesson_2_{}x{}", width, height), &render(&app, width, height));
+    }
+}
+
+#[test]
+fn hints() {
+    let mut app = app(80, 24);
+    app.send_keys("1???");
+    assert_snapshot("lesson_1_hints", &render(&app, 80, 24));
+}
+
+#[test]
+fn cursor_at_end_of_line() {
+    let mut app = app(80, 24);
+    app.send_keys("3$");
+    assert_snapshot("lesson_3_line_end", &render(&app, 80, 24));
+}
+
+#[test]
+fn wrong_answer_with_target_shown() {
+    let mut app = app(80, 24);
+    app.send_keys("2eX");
+    assert_snapshot("lesson_2_wrong_answer", &render(&app, 80, 24));
+}
+
+#[test]
+fn task_solved() {
+    let mut app = app(80, 24);
+    app.send_keys("1l");
+    assert_snapshot("lesson_1_task_solved", &render(&app, 80, 24));
+}
+
+#[test]
+fn lesson_completed() {
+    let mut app = app(80, 24);
+    let lesson = app.curriculum.get_lesson(1).unwrap();
+    let keys: Vec<String> = lesson
+        .tasks
+        .iter()
+        .map(|task| {
+            task.expected_commands
+                .iter()
+                .filter_map(|c| c.as_motion())
+                .map(|m| m.to_key_str())
+                .collect()
+        })
+        .collect();
+    app.send_keys(&format!("1{}", keys.join("<Space>")));
+    assert_snapshot("lesson_1_completed", &render(&app, 80, 24));
+}
+
+#[test]
+fn quiz_guess_and_answer() {
+    let mut app = app(80, 24);
+    app.send_keys("7lll");
+    assert_snapshot("lesson_7_guess", &render(&app, 80, 24));
+
+    app.send_keys("lllllll<CR>");
+    assert_snapshot("lesson_7_answered", &render(&app, 80, 24));
+}
@@ -0,0 +1,144 @@
+//! Replays the motion fixtures recorded from real Vim through VEX's engine.
+//! `tests/fixtures/motions.txt` describes its format; regenerate it with
+//! `tests/fixtures/generate.vim` after adding cases (this needs Vim, the
+//! test does not).
+
+use std::fs;
+
+use vex::vim::parser::ParseResult;
+use vex::vim::{Buffer, CommandParser, CursorPosition, Executor, Key, Options};
+
+const FIXTURES: &str = "tests/fixtures/motions.txt";
+
+/// One recorded case, with the line of the file it came from.
+#[derive(Debug)]
+struct Case {
+    line_number: usize,
+    start: CursorPosition,
+    keys: String,
+    expected: CursorPosition,
+}
+
+/// A buffer, the options set for it, and the cases run on it.
+#[derive(Debug)]
+struct Block {
+    lines: Vec<String>,
+    set: Vec<String>,
+    cases: Vec<Case>,
+}
+
+fn parse_position(text: &str) -> Result<CursorPosition, String> {
+    let (line, column) = text.split_once(',').ok_or("expected <line>,<col>")?;
+    let parse = |n: &str| n.parse::<usize>().map_err(|e| format!("{:?}: {}", n, e));
+    Ok(CursorPosition::new(parse(line)?, parse(column)?))
+}
+
+fn parse_fixtures(text: &str) -> Result<Vec<Block>, String> {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let at_line = |e: String| format!("{}:{}: {}", FIXTURES, index + 1, e);
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(json) = line.strip_prefix("buffer ") {
+            let lines = serde_json::from_str(json).map_err(|e| at_line(e.to_string()))?;
+            blocks.push(Block {
+                lines,
+                set: Vec::new(),
+                cases: Vec::new(),
+            });
+            continue;
+        }
+
+        let block = blocks
+            .last_mut()
+            .ok_or_else(|| at_line("case before the first buffer".to_string()))?;
+        if let Some(options) = line.strip_prefix("set ") {
+            block.set = options.split_whitespace().map(str::to_string).collect();
+            continue;
+        }
+
+        let [start, keys, expected] = line.split(' ').collect::<Vec<_>>()[..] else {
+            return Err(at_line(format!("expected <start> <keys> <cursor>, got {:?}", line)));
+        };
+        block.cases.push(Case {
+            line_number: index + 1,
+            start: parse_position(start).map_err(at_line)?,
+            keys: keys.to_string(),
+            expected: parse_position(expected).map_err(at_line)?,
+        });
+    }
+    Ok(blocks)
+}
+
+/// Types `keys` from `start` like a learner would: every complete command
+/// runs, and one that fails leaves the cursor where the executor put it.
+fn replay(buffer: &Buffer, start: CursorPosition, keys: &str) -> Buffer {
+    let mut buffer = buffer.clone();
+    buffer.set_cursor(start);
+    let mut parser = CommandParser::new();
+
+    for key in Key::parse_sequence(keys) {
+        if let ParseResult::Complete(command) = parser.parse_keystroke(key) {
+            let _ = Executor::execute(&mut buffer, command);
+        }
+    }
+    buffer
+}
+
+#[test]
+fn test_motions_match_vim() {
+    let text = fs::read_to_string(FIXTURES).expect("the fixtures are checked in");
+    let blocks = parse_fixtures(&text).unwrap();
+
+    let mut count = 0;
+    let mut failures = Vec::new();
+    for block in &blocks {
+        let mut options = Options::default();
+        options.set_all(block.set.iter().map(String::as_str)).unwrap();
+        let mut buffer = Buffer::from_lines(block.lines.clone());
+        buffer.set_options(options);
+
+        for case in &block.cases {
+            count += 1;
+            let result = replay(&buffer, case.start, &case.keys);
+            if result.cursor() != case.expected || result.lines() != block.lines {
+                failures.push(format!(
+                    "line {}: {:?} {} from {},{}: Vim {},{}, VEX {},{}",
+                    case.line_number,
+                    block.lines,
+                    case.keys,
+                    case.start.line,
+                    case.start.column,
+                    case.expected.line,
+                    case.expected.column,
+                    result.cursor().line,
+                    result.cursor().column,
+                ));
+            }
+        }
+    }
+
+    assert!(count > 8_000, "only {} fixtures", count);
+    assert!(
+        failures.is_empty(),
+        "{} of {} fixtures differ from Vim:\n{}",
+        failures.len(),
+        count,
+        failures.iter().take(40).cloned().collect::<Vec<_>>().join("\n")
+    );
+}
+
+#[test]
+fn test_parse_fixtures() {
+    let blocks = parse_fixtures("# comment\n\nbuffer [\"a b\", \"\"]\nset ic ws\n0,0 w 0,2\n").unwrap();
+    assert_eq!(blocks.len(), 1);
+    assert_eq!(blocks[0].lines, vec!["a b", ""]);
+    assert_eq!(blocks[0].set, vec!["ic", "ws"]);
+    assert_eq!(blocks[0].cases[0].expected, CursorPosition::new(0, 2));
+
+    assert!(parse_fixtures("0,0 w 0,2").unwrap_err().contains("before the first buffer"));
+    assert!(parse_fixtures("buffer [\"\"]\n0,0 w").unwrap_err().contains(":2:"));
+}